@@ -159,6 +159,22 @@ export class HybridSimClient {
     } as WorkerMsg);
   }
   
+  // Record a trail of one entity for the debug overlay (single-worker mode only); the worker
+  // posts it as 'followTrail' messages
+  follow(index: number, ticks: number) {
+    this._worker?.postMessage({
+      type: 'follow',
+      payload: { index, ticks },
+    } as WorkerMsg);
+  }
+  
+  stopFollowing() {
+    this._worker?.postMessage({
+      type: 'follow',
+      payload: null,
+    } as WorkerMsg);
+  }
+  
   onMessage(listener: (msg: MainMsg) => void): () => void {
     this.listeners.push(listener);
     return () => {
@@ -2,14 +2,30 @@ import { useRef, useMemo } from 'react';
 import { useFrame } from '@react-three/fiber';
 import * as THREE from 'three';
 import { Line } from '@react-three/drei';
+import type { FollowTrail } from '../sim/types';
+import { worldToSphere } from './utils/coordinateTransform';
 
 interface DebugArrowsProps {
   planetRadius: number;
   sunRotation: number;
   staticSunPosition?: THREE.Vector3;
+  // Followed entity from SimCore.get_follow_trail(), drawn as a path plus arrows to its targets
+  followTrail?: FollowTrail | null;
+  worldWidth?: number;
+  worldHeight?: number;
 }
 
-export function DebugArrows({ planetRadius, sunRotation, staticSunPosition }: DebugArrowsProps) {
+// Lift overlay lines slightly off the surface so they aren't hidden by terrain
+const TRAIL_LIFT = 1.01;
+
+export function DebugArrows({
+  planetRadius,
+  sunRotation,
+  staticSunPosition,
+  followTrail,
+  worldWidth,
+  worldHeight,
+}: DebugArrowsProps) {
   const lastLogTime = useRef(0);
   const arrowRef = useRef<any>(null);
   const sphereRef = useRef<THREE.Mesh>(null);
@@ -60,6 +76,26 @@ export function DebugArrows({ planetRadius, sunRotation, staticSunPosition }: De
     ];
   }, [sunPosition, planetRadius]);
   
+  // Followed entity's path and arrows from its latest position to what it is attending to
+  const trail = useMemo(() => {
+    if (!followTrail || !worldWidth || !worldHeight || followTrail.frames.length === 0) {
+      return null;
+    }
+    const toSphere = ([x, y]: [number, number]) =>
+      worldToSphere(x, y, worldWidth, worldHeight, planetRadius * TRAIL_LIFT);
+    
+    const path = followTrail.frames.map(frame => toSphere(frame.position));
+    const last = followTrail.frames[followTrail.frames.length - 1];
+    const head = path[path.length - 1];
+    const targets = [
+      { point: last.prey, color: 'red' },
+      { point: last.rival, color: 'orange' },
+      { point: last.food, color: 'lime' },
+    ].flatMap(({ point, color }) => (point ? [{ points: [head, toSphere(point)], color }] : []));
+    
+    return { path, head, targets };
+  }, [followTrail, worldWidth, worldHeight, planetRadius]);
+  
   // Dynamic camera arrow needs to be created in component
   const cameraArrowRef = useRef<THREE.Vector3[]>([
     new THREE.Vector3(0, 0, 0),
@@ -87,6 +123,22 @@ export function DebugArrows({ planetRadius, sunRotation, staticSunPosition }: De
       </mesh>
       
       {/* Actual sun position indicator (far away) - removed to prevent crashes */}
+      
+      {/* Followed entity: path in cyan, targets in red (prey), orange (rival), lime (food) */}
+      {trail && (
+        <>
+          {trail.path.length > 1 && (
+            <Line points={trail.path} color="cyan" lineWidth={2} />
+          )}
+          <mesh position={trail.head}>
+            <sphereGeometry args={[4, 8, 8]} />
+            <meshBasicMaterial color={followTrail?.alive ? 'cyan' : 'gray'} />
+          </mesh>
+          {trail.targets.map(({ points, color }) => (
+            <Line key={color} points={points} color={color} lineWidth={2} dashed />
+          ))}
+        </>
+      )}
     </group>
   );
 }
\ No newline at end of file
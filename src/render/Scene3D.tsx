@@ -10,9 +10,10 @@ import { CloudSystemProcedural } from './CloudLayerProcedural';
 import { DebugArrows } from './DebugArrows';
 import { DevControls3D } from '../ui/DevControls3D';
 import type { SimClient } from '../client/setupSimClientHybrid';
-import type { MainMsg } from '../sim/types';
+import type { MainMsg, FollowTrail } from '../sim/types';
 
 const PLANET_RADIUS = 500;
+const FOLLOW_TRAIL_TICKS = 600; // ~10s of trail at 60Hz
 const AXIAL_TILT = 23.5 * Math.PI / 180; // Earth's axial tilt
 
 // FPS tracking component (reused from Scene2D)
@@ -271,6 +272,7 @@ export function Scene3D({ client, world, entitySize }: Scene3DProps) {
   const [showMoon, setShowMoon] = useState(true);
   const [showSun, setShowSun] = useState(true);
   const [showDebug, setShowDebug] = useState(false);
+  const [followTrail, setFollowTrail] = useState<FollowTrail | null>(null);
   
   // Static sun position
   const staticSunPosition = useMemo(() => {
@@ -283,11 +285,23 @@ export function Scene3D({ client, world, entitySize }: Scene3DProps) {
     const unsubscribe = client.onMessage((msg: MainMsg) => {
       if (msg.type === 'pauseState') {
         setIsPaused(msg.payload.paused);
+      } else if (msg.type === 'followTrail') {
+        setFollowTrail(msg.payload);
       }
     });
     return unsubscribe;
   }, [client]);
   
+  // Follow an entity while the debug overlay is shown
+  useEffect(() => {
+    if (!showDebug) return;
+    client.follow(0, FOLLOW_TRAIL_TICKS);
+    return () => {
+      client.stopFollowing();
+      setFollowTrail(null);
+    };
+  }, [client, showDebug]);
+  
   // Set up initial camera position
   const initialCameraPosition: [number, number, number] = [
     PLANET_RADIUS * 2.5,
@@ -425,6 +439,9 @@ export function Scene3D({ client, world, entitySize }: Scene3DProps) {
             planetRadius={PLANET_RADIUS}
             sunRotation={0}  // Static sun
             staticSunPosition={staticSunPosition}
+            followTrail={followTrail}
+            worldWidth={world.width}
+            worldHeight={world.height}
           />
         )}
       </Canvas>
@@ -3,6 +3,7 @@ import { FoodSystem } from './foodSystem';
 import { SpatialHash } from '../spatialHash';
 import { efficientMovementOptimized } from '../spatialBehaviorsOptimized';
 import { createRng, type Rng } from '../random';
+import type { WasmMirror } from '../wasmMirror';
 import { energyConfig, GENE_COUNT, FIXED_TIMESTEP as _FIXED_TIMESTEP } from './constants';
 import type { SimStats } from '../types';
 
@@ -58,6 +59,8 @@ export class SimulationCore {
   private traversabilityCache: Map<number, boolean> = new Map();
   // WASM collision map if available
   wasmCollisionMap?: any; // BiomeCollisionMap instance
+  // WASM core fed with births and deaths (single-worker mode only)
+  wasmMirror?: WasmMirror;
   workerRegion?: { x: number; y: number; width: number; height: number; x2: number; y2: number };
   isMultiWorker: boolean = false;
   startIdx: number = 0;  // Start index for this worker's entities
@@ -153,6 +156,8 @@ export class SimulationCore {
         this.updateEntitySingleWorker(i, dt);
       }
     }
+    
+    this.wasmMirror?.sync(this.entities, dt);
   }
 
   private updateEntityMultiWorker(i: number, dt: number) {
@@ -429,6 +434,7 @@ export class SimulationCore {
     // Death checks
     if (this.entities.energy[i] <= 0 || this.entities.age[i] > energyConfig.deathAge) {
       this.entities.kill(i);
+      this.wasmMirror?.death(i, this.entities.energy[i] <= 0 ? 'starved' : 'oldAge');
       this.deathsByTribe[this.entities.tribeId[i]]++;
       if (this.entities.energy[i] <= 0) {
         this.starvedByTribe[this.entities.tribeId[i]]++;
@@ -472,7 +478,8 @@ export class SimulationCore {
         this.fullVel || undefined,
         this.biomeTraversability ? {
           isTraversable: (x: number, y: number) => this.isTraversable(x, y)
-        } : undefined
+        } : undefined,
+        this.wasmMirror
       );
       
       // Apply velocity with strict biome collision detection
@@ -509,6 +516,7 @@ export class SimulationCore {
         if (!foundValid) {
           // Couldn't find valid position - kill entity
           this.entities.alive[i] = 0;
+          this.wasmMirror?.death(i, 'unknown');
         }
       }
       
@@ -626,6 +634,7 @@ export class SimulationCore {
           if (!this.entities.alive[j]) {
             if (this.entities.reproduce(i, j, this.rand, this.tribeColors, this.worldWidth, this.worldHeight)) {
               this.birthsByTribe[this.entities.tribeId[i]]++;
+              this.wasmMirror?.birth(j, i);
               if (j >= this.count) this.count = j + 1;
               break;
             }
@@ -7,6 +7,8 @@ import { defaultGenes } from './genes';
 import type { WorkerMsg, MainMsg, PerfStats } from './types';
 import { WORLD_WIDTH, WORLD_HEIGHT } from './core/constants';
 import { loadWasmModule, isWasmSupported } from './wasmLoader';
+import { WasmMirror } from './wasmMirror';
+import { decodeSimEvents } from './simEvents';
 
 // Helper function for color conversion
 function hslToRgb(h: number, s: number, l: number): [number, number, number] {
@@ -75,6 +77,9 @@ let stepTimeAccum = 0;
 let maxStepTime = 0;
 let stepsSincePerf = 0; // Count actual simulation steps for perf metrics
 
+// Whether the main thread asked for a follow trail
+let following = false;
+
 function initializeAsMainWorker(msg: any) {
   const init = msg.payload;
   const seed = init.seed || Date.now();
@@ -263,6 +268,21 @@ function initializeAsMainWorker(msg: any) {
   });
   sim.entities.count = totalSpawned;
   
+  // Mirror births and deaths into a WASM core for lineage, counters and events. Sub-workers
+  // only see part of the population, so multi-worker runs don't get one.
+  if (isWasmSupported()) {
+    loadWasmModule().then(wasmModule => {
+      if (wasmModule && wasmModule.SimCore && sim) {
+        const core = new wasmModule.SimCore(cap, worldWidth, worldHeight, 80);
+        sim.tribeNames.forEach((name, tribe) => core.set_tribe_info(tribe, name, sim!.tribeColors[tribe] || 0));
+        core.set_events_enabled(true);
+        sim.wasmMirror = new WasmMirror(core, cap);
+      }
+    }).catch(err => {
+      console.log('[Worker] WASM core not available, lineage tracking disabled:', err.message);
+    });
+  }
+  
   // Send ready message with expected buffer names
   const foodMeta = sharedBuffers!.foodGrid && sim.food ? 
     { cols: sim.food.getCols(), rows: sim.food.getRows() } : 
@@ -299,6 +319,19 @@ function initializeAsMainWorker(msg: any) {
     if (sim) {
       const stats = sim.getStats();
       self.postMessage({ type: 'stats', payload: stats } as MainMsg);
+      
+      // Births, deaths and kills recorded by the WASM core since the last drain
+      if (sim.wasmMirror) {
+        const batch = decodeSimEvents(sim.wasmMirror.core.take_events());
+        if (batch.events.length > 0 || batch.dropped > 0) {
+          self.postMessage({ type: 'simEvents', payload: batch } as MainMsg);
+        }
+        
+        if (following) {
+          const trail = sim.wasmMirror.core.get_follow_trail();
+          self.postMessage({ type: 'followTrail', payload: trail } as MainMsg);
+        }
+      }
     }
   }, 500);
 }
@@ -944,6 +977,29 @@ self.addEventListener('message', (e: MessageEvent<WorkerMsg>) => {
       renderFps = msg.payload.fps;
       break;
       
+    case 'follow':
+      if (sim && sim.wasmMirror) {
+        following = msg.payload !== null;
+        if (msg.payload) {
+          const { index, ticks } = msg.payload;
+          const alive = sim.entities.alive;
+          let target = -1;
+          for (let n = 0; n < alive.length; n++) {
+            const i = (index + n) % alive.length;
+            if (alive[i]) {
+              target = i;
+              break;
+            }
+          }
+          if (target >= 0) {
+            sim.wasmMirror.core.follow_entity(target, ticks);
+          }
+        } else {
+          sim.wasmMirror.core.stop_following();
+        }
+      }
+      break;
+      
     case 'updateFoodParams':
       if (sim && sim.food) {
         const { capacity, regen } = msg.payload;
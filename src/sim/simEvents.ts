@@ -0,0 +1,83 @@
+// Decoder for the binary event buffer returned by SimCore.take_events()
+// (layout documented in wasm/src/events.rs)
+
+export type DeathCause = 'starved' | 'oldAge' | 'killed' | 'unknown';
+
+export type SimEvent =
+  | { kind: 'birth'; tick: number; child: bigint; parents: bigint[]; slot: number; tribe: number }
+  | { kind: 'death'; tick: number; id: bigint; slot: number; cause: DeathCause; x: number; y: number }
+  | {
+      kind: 'kill';
+      tick: number;
+      killer: bigint;
+      victim: bigint;
+      energy: number;       // energy the killer gained
+      killerSlot: number;
+      victimSlot: number;
+      x: number;            // victim position
+      y: number;
+    }
+  | { kind: 'biomeEnter'; tick: number; id: bigint; slot: number; biome: number; from: number }
+  | { kind: 'speciesFormed'; tick: number; species: number; members: number };
+
+export type SimEventBatch = {
+  events: SimEvent[];
+  dropped: number; // events lost because the queue was full between drains
+};
+
+const MAGIC = 'EVT1';
+const DEATH_CAUSES: DeathCause[] = ['starved', 'oldAge', 'killed', 'unknown'];
+
+export function decodeSimEvents(bytes: Uint8Array): SimEventBatch {
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  if (bytes.byteLength < 12 || String.fromCharCode(...bytes.subarray(0, 4)) !== MAGIC) {
+    throw new Error('Not a sim event buffer');
+  }
+
+  const count = view.getUint32(4, true);
+  const dropped = view.getUint32(8, true);
+  const events: SimEvent[] = [];
+  let p = 12;
+
+  const u8 = () => view.getUint8(p++);
+  const u16 = () => { const v = view.getUint16(p, true); p += 2; return v; };
+  const u32 = () => { const v = view.getUint32(p, true); p += 4; return v; };
+  const u64 = () => { const v = view.getBigUint64(p, true); p += 8; return v; };
+  const f32 = () => { const v = view.getFloat32(p, true); p += 4; return v; };
+
+  for (let n = 0; n < count; n++) {
+    const kind = u8();
+    const tick = u32();
+    switch (kind) {
+      case 0: {
+        const child = u64();
+        // Missing parents (founders, asexual births) are written as 0
+        const parents = [u64(), u64()].filter(id => id !== 0n);
+        events.push({ kind: 'birth', tick, child, parents, slot: u32(), tribe: u16() });
+        break;
+      }
+      case 1:
+        events.push({
+          kind: 'death', tick, id: u64(), slot: u32(),
+          cause: DEATH_CAUSES[u8()] ?? 'unknown', x: f32(), y: f32(),
+        });
+        break;
+      case 2:
+        events.push({
+          kind: 'kill', tick, killer: u64(), victim: u64(), energy: f32(),
+          killerSlot: u32(), victimSlot: u32(), x: f32(), y: f32(),
+        });
+        break;
+      case 3:
+        events.push({ kind: 'biomeEnter', tick, id: u64(), slot: u32(), biome: u8(), from: u8() });
+        break;
+      case 4:
+        events.push({ kind: 'speciesFormed', tick, species: u32(), members: u32() });
+        break;
+      default:
+        throw new Error(`Unknown sim event kind ${kind} at byte ${p - 5}`);
+    }
+  }
+
+  return { events, dropped };
+}
@@ -2,6 +2,7 @@
 // Maintains exact same behavior but with better performance
 import { SpatialHash } from './spatialHash';
 import { energyConfig } from './core/constants';
+import type { WasmMirror } from './wasmMirror';
 interface BiomeChecker {
   isTraversable: (x: number, y: number) => boolean;
 }
@@ -63,7 +64,9 @@ export function efficientMovementOptimized(
   fullGenes?: Float32Array,
   fullEnergy?: Float32Array,
   fullVel?: Float32Array,
-  biomeChecker?: BiomeChecker
+  biomeChecker?: BiomeChecker,
+  // Reports kills and hybrid births to the WASM core (single-worker mode)
+  wasmMirror?: WasmMirror
 ): void {
   const G = 9;
   const base = i * G;
@@ -570,12 +573,14 @@ export function efficientMovementOptimized(
         
         if (energy[target] <= 0) {
           alive[target] = 0;
+          let digestibleEnergy = 0;
           if (carnivoreLevel > 0) {
             // Corpse provides less energy, encouraging sustainable hunting
             const corpseEnergy = Math.min(30, targetEnergy * 0.5); // Much less than before
-            const digestibleEnergy = corpseEnergy * carnivoreLevel; // No 1.5x multiplier
+            digestibleEnergy = corpseEnergy * carnivoreLevel; // No 1.5x multiplier
             energy[i] += digestibleEnergy;
           }
+          wasmMirror?.kill(i, target, digestibleEnergy);
           if (killsByTribe && deathsByTribe) {
             killsByTribe[myTribe]++;
             deathsByTribe[tribeId[target]]++;
@@ -613,6 +618,7 @@ export function efficientMovementOptimized(
             alive[j] = 1;
             energy[j] = 40;
             tribeId[j] = 999; // Hybrid tribe
+            wasmMirror?.birth(j, i, potentialMate);
             
             // Set hybrid genes
             const jBase = j * G;
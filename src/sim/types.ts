@@ -1,3 +1,5 @@
+import type { SimEventBatch } from './simEvents';
+
 export type Seed = number;
 
 export type GeneSpec = {
@@ -132,6 +134,81 @@ export type SimStats = {
   };
 };
 
+export type BehaviorStateName = 'Foraging' | 'Migrating' | 'Hunting' | 'Fleeing' | 'Resting';
+
+// Entity ids are BigInt, as from SimCore.get_entity_id() and the event stream
+export type InspectedTarget = {
+  index: number;
+  id: bigint;
+  tribe: number;
+  position: [number, number];
+  distance: number;
+};
+
+// Returned by SimCore.inspect(index)
+export type EntityInspection = {
+  index: number;
+  id: bigint;
+  alive: boolean;
+  tribe: number;
+  species: number | null;
+  position: [number, number];
+  velocity: [number, number];
+  speed: number;
+  energy: number;
+  age: number;
+  panic: number;
+  crowdStress: number;
+  packBonus: number;
+  enemyStrength: number;
+  neural: boolean;            // steered by the tribe's evolved brain
+  genes: Record<string, number>;
+  behavior: {
+    state: BehaviorStateName;
+    timer: number;            // seconds in the current state
+    scarcity: number;
+    heading: [number, number];
+  };
+  targets: {
+    prey: InspectedTarget | null;
+    rival: InspectedTarget | null;
+    mate: InspectedTarget | null; // partner reproduction would pick right now
+    food: { col: number; row: number; position: [number, number]; food: number; distance: number } | null;
+  };
+  neighbors: {
+    allies: number;           // as seen by the last movement pass
+    enemies: number;
+    inVision: number;         // everyone within vision radius
+    sameTribe: number;
+    sameSpecies: number;
+  };
+  lineage: {
+    parents: bigint[];
+    birthTick: number;
+    generation: number;
+    livingChildren: number;
+  } | null;
+};
+
+export type FollowFrame = {
+  tick: number;
+  position: [number, number];
+  velocity: [number, number];
+  energy: number;
+  state: BehaviorStateName;
+  prey: [number, number] | null;
+  rival: [number, number] | null;
+  food: [number, number] | null;
+};
+
+// Returned by SimCore.get_follow_trail() while following an entity (oldest frame first)
+export type FollowTrail = {
+  id: bigint;
+  index: number;
+  alive: boolean;
+  frames: FollowFrame[];
+};
+
 export type WorkerMsg =
   | { type: 'init'; payload: SimInit }
   | { type: 'init-sub-worker'; payload: any }
@@ -142,7 +219,9 @@ export type WorkerMsg =
   | { type: 'renderFps'; payload: { fps: number } }
   | { type: 'updateFoodParams'; payload: { capacity?: number; regen?: number } }
   | { type: 'stats' }
-  | { type: 'perf' };
+  | { type: 'perf' }
+  // Follow the first living entity at or after `index` (null stops following)
+  | { type: 'follow'; payload: { index: number; ticks: number } | null };
 
 export type PerfStats = {
   simHz: number;     // simulation Hz
@@ -189,4 +268,6 @@ export type MainMsg =
   | { type: 'perf'; payload: PerfStats }
   | { type: 'perfBreakdown'; payload: PerfBreakdown }
   | { type: 'foodUpdate'; payload: { foodGrid: ArrayBuffer } }
-  | { type: 'extinction'; payload: { finalTime: number; finalStats: SimStats } };
\ No newline at end of file
+  | { type: 'extinction'; payload: { finalTime: number; finalStats: SimStats } }
+  | { type: 'simEvents'; payload: SimEventBatch }
+  | { type: 'followTrail'; payload: FollowTrail | null };
\ No newline at end of file
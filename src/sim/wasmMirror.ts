@@ -0,0 +1,106 @@
+// Keeps a wasm SimCore in step with the single-worker JS simulation so its lineage, counters
+// and event queue see every birth and death. JS reports them as they happen during a step;
+// sync() then loads the step's entity state into the core and replays the reports in order.
+import type { SimCore } from '../../wasm/pkg/gene_sim_core';
+import type { EntitySystem } from './core/entitySystem';
+import type { DeathCause } from './simEvents';
+import { GENE_COUNT } from './core/constants';
+
+// Matches the DeathCause enum in wasm/src/types.rs
+const DEATH_CAUSE_CODES: Record<DeathCause, number> = {
+  starved: 0,
+  oldAge: 1,
+  killed: 2,
+  unknown: 3,
+};
+
+type MirrorOp =
+  | { kind: 'birth'; child: number; parent: number; mate: number }
+  | { kind: 'death'; index: number; cause: DeathCause }
+  | { kind: 'kill'; killer: number; victim: number; energy: number };
+
+export class WasmMirror {
+  core: SimCore;
+
+  // Reports from the current step, replayed in order so a slot that dies and is reused
+  // (or a child that dies right after birth) is recorded correctly
+  private ops: MirrorOp[] = [];
+
+  // De-interleaved copies of the JS pos/vel buffers, reused every sync
+  private posX: Float32Array;
+  private posY: Float32Array;
+  private velX: Float32Array;
+  private velY: Float32Array;
+  private alive: Uint8Array;
+
+  constructor(core: SimCore, cap: number) {
+    this.core = core;
+    this.posX = new Float32Array(cap);
+    this.posY = new Float32Array(cap);
+    this.velX = new Float32Array(cap);
+    this.velY = new Float32Array(cap);
+    this.alive = new Uint8Array(cap);
+  }
+
+  // Pass -1 for a missing mate (asexual reproduction)
+  birth(child: number, parent: number, mate: number = -1) {
+    this.ops.push({ kind: 'birth', child, parent, mate });
+  }
+
+  death(index: number, cause: DeathCause) {
+    this.ops.push({ kind: 'death', index, cause });
+  }
+
+  // `energy` is what the killer gained from the victim
+  kill(killer: number, victim: number, energy: number) {
+    this.ops.push({ kind: 'kill', killer, victim, energy });
+  }
+
+  // Call once at the end of every step
+  sync(entities: EntitySystem, dt: number) {
+    const cap = entities.cap;
+    const pos = entities.pos;
+    const vel = entities.vel;
+    for (let i = 0; i < cap; i++) {
+      this.posX[i] = pos[i * 2];
+      this.posY[i] = pos[i * 2 + 1];
+      this.velX[i] = vel[i * 2];
+      this.velY[i] = vel[i * 2 + 1];
+    }
+
+    // Children born this step are registered with their parents below, not as founders
+    this.alive.set(entities.alive);
+    for (const op of this.ops) {
+      if (op.kind === 'birth') this.alive[op.child] = 0;
+    }
+
+    this.core.load_from_buffers(
+      this.posX,
+      this.posY,
+      this.velX,
+      this.velY,
+      entities.energy,
+      this.alive,
+      entities.tribeId,
+      entities.genes,
+      GENE_COUNT
+    );
+
+    for (const op of this.ops) {
+      switch (op.kind) {
+        case 'birth':
+          this.core.register_birth(op.child, op.parent, op.mate);
+          break;
+        case 'death':
+          this.core.register_death(op.index, DEATH_CAUSE_CODES[op.cause]);
+          break;
+        case 'kill':
+          this.core.register_kill(op.killer, op.victim, op.energy);
+          break;
+      }
+    }
+    this.ops.length = 0;
+
+    this.core.finish_tick(dt);
+  }
+}
@@ -0,0 +1,340 @@
+// Population dynamics analysis over recorded predator/prey time series
+// Series are assumed to be sampled at a (roughly) fixed interval, as the history recorder does.
+//
+// Lotka–Volterra fit:  dx/dt = αx − βxy,  dy/dt = δxy − γy   (x = prey, y = predators)
+// Dividing by the population turns both into linear models of the per-capita growth rates,
+//   d ln x/dt = α − β·y      d ln y/dt = δ·x − γ
+// which are fitted by least squares on finite differences between consecutive samples.
+//
+// Oscillations come from the autocorrelation of the mean-removed series: the period is the
+// lag of the first autocorrelation peak after the first zero crossing.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    // A drop below this fraction of the preceding peak counts as a collapse
+    pub collapse_fraction: f64,
+    // Populations below this are not considered for collapses (noise around tiny counts)
+    pub collapse_min_peak: f64,
+    // Minimum autocorrelation at the period lag to report an oscillation
+    pub min_autocorrelation: f64,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            collapse_fraction: 0.1,
+            collapse_min_peak: 20.0,
+            min_autocorrelation: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotkaVolterra {
+    // Prey growth, predation rate, predator death rate, conversion efficiency
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub delta: f64,
+    // Goodness of fit of the two per-capita growth regressions
+    pub prey_r2: f64,
+    pub predator_r2: f64,
+    // Sample pairs used (both populations positive)
+    pub samples: usize,
+}
+
+impl LotkaVolterra {
+    // Interior equilibrium (prey, predators) of the fitted model, if it exists
+    pub fn equilibrium(&self) -> Option<(f64, f64)> {
+        (self.beta > 0.0 && self.delta > 0.0).then(|| (self.gamma / self.delta, self.alpha / self.beta))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Oscillation {
+    // Seconds per cycle
+    pub period: f64,
+    // Half the mean peak-to-trough swing per cycle
+    pub amplitude: f64,
+    // Autocorrelation at the period lag (1 = perfectly periodic)
+    pub strength: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesSummary {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    #[serde(rename = "final")]
+    pub last: f64,
+    pub oscillation: Option<Oscillation>,
+    // Time the population first hit zero for good (None if it survived)
+    pub extinction_time: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Series {
+    Prey,
+    Predator,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PopulationEvent {
+    Extinction {
+        series: Series,
+        time: f64,
+    },
+    // Fell below collapse_fraction of the preceding peak
+    Collapse {
+        series: Series,
+        time: f64,
+        peak: f64,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PopulationReport {
+    pub prey: SeriesSummary,
+    pub predator: SeriesSummary,
+    pub lotka_volterra: Option<LotkaVolterra>,
+    // Seconds by which predator swings trail prey swings (a quarter period in the ideal model)
+    pub predator_lag: Option<f64>,
+    pub events: Vec<PopulationEvent>,
+}
+
+// Least-squares line y = a + b·x; returns (a, b, r²)
+fn linear_fit(xs: &[f64], ys: &[f64]) -> Option<(f64, f64, f64)> {
+    let n = xs.len() as f64;
+    if xs.len() < 3 {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    let mut syy = 0.0;
+    for (&x, &y) in xs.iter().zip(ys) {
+        sxx += (x - mean_x) * (x - mean_x);
+        sxy += (x - mean_x) * (y - mean_y);
+        syy += (y - mean_y) * (y - mean_y);
+    }
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let r2 = if syy > f64::EPSILON { (sxy * sxy) / (sxx * syy) } else { 1.0 };
+    Some((mean_y - slope * mean_x, slope, r2))
+}
+
+pub fn fit_lotka_volterra(times: &[f64], prey: &[f64], predators: &[f64]) -> Option<LotkaVolterra> {
+    let n = times.len().min(prey.len()).min(predators.len());
+    let mut prey_mid = Vec::new();
+    let mut predator_mid = Vec::new();
+    let mut prey_rate = Vec::new();
+    let mut predator_rate = Vec::new();
+
+    for k in 1..n {
+        let dt = times[k] - times[k - 1];
+        let (x0, x1, y0, y1) = (prey[k - 1], prey[k], predators[k - 1], predators[k]);
+        if dt <= 0.0 || x0 <= 0.0 || x1 <= 0.0 || y0 <= 0.0 || y1 <= 0.0 {
+            continue;
+        }
+        prey_mid.push((x0 + x1) * 0.5);
+        predator_mid.push((y0 + y1) * 0.5);
+        prey_rate.push((x1 / x0).ln() / dt);
+        predator_rate.push((y1 / y0).ln() / dt);
+    }
+
+    let (alpha, neg_beta, prey_r2) = linear_fit(&predator_mid, &prey_rate)?;
+    let (neg_gamma, delta, predator_r2) = linear_fit(&prey_mid, &predator_rate)?;
+
+    Some(LotkaVolterra {
+        alpha,
+        beta: -neg_beta,
+        gamma: -neg_gamma,
+        delta,
+        prey_r2,
+        predator_r2,
+        samples: prey_rate.len(),
+    })
+}
+
+// Normalized autocorrelation of a mean-removed series for lags 0..max_lag
+fn autocorrelation(values: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = values.len();
+    let mean = values.iter().sum::<f64>() / n.max(1) as f64;
+    let centered: Vec<f64> = values.iter().map(|v| v - mean).collect();
+    let variance: f64 = centered.iter().map(|v| v * v).sum();
+    if variance <= f64::EPSILON {
+        return vec![0.0; max_lag.min(n)];
+    }
+    (0..max_lag.min(n))
+        .map(|lag| centered[..n - lag].iter().zip(&centered[lag..]).map(|(a, b)| a * b).sum::<f64>() / variance)
+        .collect()
+}
+
+// Mean sample spacing in seconds
+fn sample_interval(times: &[f64]) -> Option<f64> {
+    let span = times.last()? - times.first()?;
+    (times.len() > 1 && span > 0.0).then(|| span / (times.len() - 1) as f64)
+}
+
+pub fn detect_oscillation(times: &[f64], values: &[f64], config: &AnalysisConfig) -> Option<Oscillation> {
+    let n = times.len().min(values.len());
+    let interval = sample_interval(&times[..n])?;
+    // At least two full cycles must fit in the series
+    let acf = autocorrelation(&values[..n], n / 2 + 1);
+
+    // First local maximum past the first zero crossing (later ones are multiples of it)
+    let first_negative = acf.iter().position(|&r| r < 0.0)?;
+    let lag = (first_negative.max(1)..acf.len().saturating_sub(1))
+        .find(|&l| acf[l] > 0.0 && acf[l] >= acf[l - 1] && acf[l] >= acf[l + 1])?;
+    let strength = acf[lag];
+    if strength < config.min_autocorrelation || lag < 2 {
+        return None;
+    }
+
+    // Peak-to-trough swing within each full period window
+    let swings: Vec<f64> = values[..n]
+        .chunks_exact(lag)
+        .map(|window| {
+            let (lo, hi) = window
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            hi - lo
+        })
+        .collect();
+    let amplitude = swings.iter().sum::<f64>() / swings.len().max(1) as f64 * 0.5;
+
+    Some(Oscillation {
+        period: lag as f64 * interval,
+        amplitude,
+        strength,
+    })
+}
+
+// Lag (seconds, 0..=max_lag samples) at which `follower` best matches `leader`
+fn cross_correlation_lag(times: &[f64], leader: &[f64], follower: &[f64], max_lag: usize) -> Option<f64> {
+    let n = times.len().min(leader.len()).min(follower.len());
+    let interval = sample_interval(&times[..n])?;
+    let center = |values: &[f64]| {
+        let mean = values.iter().sum::<f64>() / n as f64;
+        values[..n].iter().map(|v| v - mean).collect::<Vec<f64>>()
+    };
+    let a = center(leader);
+    let b = center(follower);
+    let norm = (a.iter().map(|v| v * v).sum::<f64>() * b.iter().map(|v| v * v).sum::<f64>()).sqrt();
+    if norm <= f64::EPSILON {
+        return None;
+    }
+
+    (0..=max_lag.min(n - 1))
+        .map(|lag| {
+            let r = a[..n - lag].iter().zip(&b[lag..]).map(|(x, y)| x * y).sum::<f64>() / norm;
+            (lag, r)
+        })
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .map(|(lag, _)| lag as f64 * interval)
+}
+
+fn summarize(times: &[f64], values: &[f64], config: &AnalysisConfig) -> SeriesSummary {
+    if values.is_empty() {
+        return SeriesSummary {
+            mean: 0.0,
+            min: 0.0,
+            max: 0.0,
+            last: 0.0,
+            oscillation: None,
+            extinction_time: None,
+        };
+    }
+
+    SeriesSummary {
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        min: values.iter().copied().fold(f64::MAX, f64::min),
+        max: values.iter().copied().fold(f64::MIN, f64::max),
+        last: values[values.len() - 1],
+        oscillation: detect_oscillation(times, values, config),
+        extinction_time: extinction_time(times, values),
+    }
+}
+
+// Collapses (drops below collapse_fraction of the running peak, which then resets) and
+// the final extinction of one series
+fn detect_events(
+    series: Series,
+    times: &[f64],
+    values: &[f64],
+    config: &AnalysisConfig,
+    events: &mut Vec<PopulationEvent>,
+) {
+    let mut peak = 0.0f64;
+    for (&time, &value) in times.iter().zip(values) {
+        if peak >= config.collapse_min_peak && value <= peak * config.collapse_fraction {
+            events.push(PopulationEvent::Collapse {
+                series,
+                time,
+                peak,
+                value,
+            });
+            peak = value;
+        }
+        peak = peak.max(value);
+    }
+
+    if let Some(time) = extinction_time(times, values) {
+        events.push(PopulationEvent::Extinction { series, time });
+    }
+}
+
+// First sample of the final run of zeros, if the population existed and then died out
+fn extinction_time(times: &[f64], values: &[f64]) -> Option<f64> {
+    let last_alive = values.iter().rposition(|&v| v > 0.0)?;
+    if last_alive + 1 < values.len() {
+        times.get(last_alive + 1).copied()
+    } else {
+        None
+    }
+}
+
+// Full report for one predator/prey pair of series sampled at `times` (seconds)
+pub fn analyze(times: &[f64], prey: &[f64], predators: &[f64], config: &AnalysisConfig) -> PopulationReport {
+    let prey_summary = summarize(times, prey, config);
+    let predator_summary = summarize(times, predators, config);
+
+    // Search up to one prey period (or half the series without a clear cycle)
+    let max_lag = match (prey_summary.oscillation, sample_interval(times)) {
+        (Some(osc), Some(interval)) => (osc.period / interval).round() as usize,
+        _ => times.len() / 2,
+    };
+    let predator_lag = cross_correlation_lag(times, prey, predators, max_lag);
+
+    let mut events = Vec::new();
+    detect_events(Series::Prey, times, prey, config, &mut events);
+    detect_events(Series::Predator, times, predators, config, &mut events);
+    events.sort_by(|a, b| event_time(a).total_cmp(&event_time(b)));
+
+    PopulationReport {
+        prey: prey_summary,
+        predator: predator_summary,
+        lotka_volterra: fit_lotka_volterra(times, prey, predators),
+        predator_lag,
+        events,
+    }
+}
+
+fn event_time(event: &PopulationEvent) -> f64 {
+    match *event {
+        PopulationEvent::Extinction { time, .. } | PopulationEvent::Collapse { time, .. } => time,
+    }
+}
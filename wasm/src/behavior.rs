@@ -0,0 +1,157 @@
+// Per-entity behavioral state machine
+// Entities remember what they are doing (foraging, migrating, hunting, fleeing, resting) and
+// build up dissatisfaction while food is below their standards, prey is scarce or the area is
+// crowded. Once it boils over they pick a heading and migrate until they find a better place.
+
+use crate::types::BehaviorState;
+
+// Panic above which an entity drops everything and flees
+const FLEE_PANIC: f32 = 0.3;
+// Dissatisfaction (seconds-weighted) that triggers migration
+const MIGRATE_THRESHOLD: f32 = 4.0;
+// Migration lasts at least / at most this long (seconds)
+const MIN_MIGRATION: f32 = 3.0;
+const MAX_MIGRATION: f32 = 20.0;
+// Crowd stress above which the area counts as overcrowded
+const CROWD_DISCOMFORT: f32 = 0.7;
+// Satiation above which a content entity rests
+const REST_SATIATION: f32 = 0.85;
+// Largest random turn (radians) when picking a new migration heading
+const HEADING_JITTER: f32 = 1.0;
+
+// Behavioral memory stored alongside the other SoA arrays in SimCore
+pub struct BehaviorStates {
+    pub state: Vec<u8>,
+    // Seconds spent in the current state
+    pub timer: Vec<f32>,
+    // Accumulated dissatisfaction with the current area
+    pub scarcity: Vec<f32>,
+    // Persistent unit heading while migrating
+    pub heading_x: Vec<f32>,
+    pub heading_y: Vec<f32>,
+    // Food patch (world position) steered toward on the last movement pass
+    pub food_target: Vec<Option<(f32, f32)>>,
+}
+
+// What the movement pass observed this tick
+pub struct BehaviorInputs {
+    pub satiation: f32,
+    pub food_standards: f32,
+    // Food in the current cell (None without a food grid)
+    pub local_food: Option<f32>,
+    pub is_hunter: bool,
+    pub should_hunt: bool,
+    pub has_prey: bool,
+    pub panic: f32,
+    pub crowd_stress: f32,
+    pub view_dir: (f32, f32),
+    // Direction toward the center of visible allies, if any
+    pub ally_center_dir: Option<(f32, f32)>,
+}
+
+impl BehaviorStates {
+    pub fn new(capacity: usize) -> Self {
+        BehaviorStates {
+            state: vec![BehaviorState::Foraging as u8; capacity],
+            timer: vec![0.0; capacity],
+            scarcity: vec![0.0; capacity],
+            heading_x: vec![0.0; capacity],
+            heading_y: vec![0.0; capacity],
+            food_target: vec![None; capacity],
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, i: usize) -> BehaviorState {
+        BehaviorState::from_u8(self.state[i])
+    }
+
+    #[inline]
+    pub fn heading(&self, i: usize) -> (f32, f32) {
+        (self.heading_x[i], self.heading_y[i])
+    }
+
+    pub fn reset(&mut self, i: usize) {
+        self.set(i, BehaviorState::Foraging);
+        self.scarcity[i] = 0.0;
+        self.food_target[i] = None;
+    }
+
+    fn set(&mut self, i: usize, next: BehaviorState) {
+        if self.state[i] != next as u8 {
+            self.state[i] = next as u8;
+            self.timer[i] = 0.0;
+        }
+    }
+
+    // Advance entity `i` by dt and return its new state
+    pub fn update(&mut self, i: usize, input: &BehaviorInputs, dt: f32) -> BehaviorState {
+        self.timer[i] += dt;
+        let current = self.get(i);
+
+        // Picky eaters are unhappy below their standards (relaxed by hunger),
+        // hungry hunters are unhappy without prey in sight
+        let min_food = input.food_standards * (0.3 + input.satiation * 0.7);
+        let food_unmet = input.local_food.is_some_and(|food| food < min_food);
+        let prey_scarce = input.is_hunter && input.should_hunt && !input.has_prey;
+        let crowded = input.crowd_stress > CROWD_DISCOMFORT;
+
+        let mut discomfort = 0.0;
+        if prey_scarce {
+            discomfort += 1.0 - input.satiation;
+        } else if !input.is_hunter && food_unmet {
+            discomfort += 0.5 + input.food_standards;
+        }
+        if crowded {
+            discomfort += (input.crowd_stress - CROWD_DISCOMFORT) * 3.0;
+        }
+
+        if discomfort > 0.0 {
+            self.scarcity[i] += discomfort * dt;
+        } else {
+            self.scarcity[i] = (self.scarcity[i] - dt).max(0.0);
+        }
+
+        let next = if input.panic > FLEE_PANIC {
+            BehaviorState::Fleeing
+        } else if input.has_prey {
+            BehaviorState::Hunting
+        } else if current == BehaviorState::Migrating {
+            // Keep going until somewhere acceptable, or give up after a while
+            let timer = self.timer[i];
+            let arrived = timer > MIN_MIGRATION && discomfort == 0.0;
+            if arrived || timer > MAX_MIGRATION {
+                self.scarcity[i] = 0.0;
+                BehaviorState::Foraging
+            } else {
+                BehaviorState::Migrating
+            }
+        } else if self.scarcity[i] > MIGRATE_THRESHOLD {
+            self.pick_heading(i, input);
+            BehaviorState::Migrating
+        } else if input.satiation > REST_SATIATION && discomfort == 0.0 {
+            BehaviorState::Resting
+        } else {
+            BehaviorState::Foraging
+        };
+
+        self.set(i, next);
+        next
+    }
+
+    // Leave crowds behind, otherwise carry on roughly along the current heading
+    fn pick_heading(&mut self, i: usize, input: &BehaviorInputs) {
+        let (base_x, base_y) = match input.ally_center_dir {
+            Some((cx, cy)) if input.crowd_stress > CROWD_DISCOMFORT => (-cx, -cy),
+            _ => input.view_dir,
+        };
+
+        // Deterministic jitter so neighbors don't all leave in lockstep
+        let seed = (i as f32 * 12.9898 + self.scarcity[i] * 78.233).sin() * 43_758.547;
+        let turn = (seed.fract() * 2.0 - 1.0) * HEADING_JITTER;
+        let (sin, cos) = turn.sin_cos();
+
+        self.heading_x[i] = base_x * cos - base_y * sin;
+        self.heading_y[i] = base_x * sin + base_y * cos;
+    }
+}
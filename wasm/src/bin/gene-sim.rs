@@ -0,0 +1,165 @@
+// Headless runner: simulate a scenario without the browser and write analysis tables
+//
+//   cargo run --release --bin gene-sim -- run scenario.json --out runs/baseline --seed 3
+//   cargo run --release --bin gene-sim -- sweep sweep.json --out runs/food-sweep
+//
+// `run` writes history.csv and entities.csv to the output directory, plus history.arrow and
+// entities.arrow when built with --features arrow. `sweep` writes summary.csv (one row per
+// configuration) and runs.csv (one row per seed). See src/scenario.rs and src/sweep.rs for
+// the file formats.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use gene_sim_core::scenario::Scenario;
+use gene_sim_core::sweep::{self, SweepSpec};
+use serde_json::Value;
+
+#[derive(Parser)]
+#[command(name = "gene-sim", about = "Headless gene-sim runs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one scenario and export its history and entity dumps
+    Run(RunArgs),
+    /// Run a parameter grid over many seeds in parallel and summarize each configuration
+    Sweep(SweepArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Scenario JSON file
+    scenario: PathBuf,
+    /// Output directory
+    #[arg(long, default_value = "out")]
+    out: PathBuf,
+    /// Override the scenario seed
+    #[arg(long)]
+    seed: Option<u32>,
+    /// Override the number of ticks
+    #[arg(long)]
+    ticks: Option<u64>,
+}
+
+#[derive(Args)]
+struct SweepArgs {
+    /// Sweep JSON file
+    sweep: PathBuf,
+    /// Output directory
+    #[arg(long, default_value = "out")]
+    out: PathBuf,
+    /// Worker threads (default: one per core)
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+fn load_scenario(path: &Path) -> Result<Scenario, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_file(dir: &Path, name: &str, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    let path = dir.join(name);
+    fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn run(args: &RunArgs) -> Result<(), String> {
+    let mut scenario = load_scenario(&args.scenario)?;
+    if let Some(seed) = args.seed {
+        scenario.set_seed(seed);
+    }
+    if let Some(ticks) = args.ticks {
+        scenario.set_ticks(ticks);
+    }
+
+    let mut sim = scenario.build()?;
+    let dt = scenario.dt();
+    for _ in 0..scenario.ticks() {
+        sim.step(dt);
+        if sim.living_count() == 0 {
+            break;
+        }
+    }
+    // Always include the final population
+    sim.dump_entities();
+
+    fs::create_dir_all(&args.out).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+    write_file(&args.out, "history.csv", sim.export_history_csv(0.0, f64::MAX))?;
+    write_file(&args.out, "entities.csv", sim.export_entities_csv())?;
+    #[cfg(feature = "arrow")]
+    {
+        write_file(&args.out, "history.arrow", sim.export_history_arrow(0.0, f64::MAX)?)?;
+        write_file(&args.out, "entities.arrow", sim.export_entities_arrow()?)?;
+    }
+
+    println!(
+        "seed {}: {} ticks, {} alive, {} history samples, {} entity rows -> {}",
+        scenario.seed(),
+        sim.get_tick(),
+        sim.living_count(),
+        sim.history_len(),
+        sim.entity_dump_len(),
+        args.out.display(),
+    );
+    Ok(())
+}
+
+fn load_sweep(path: &Path) -> Result<SweepSpec, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut spec: SweepSpec =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    // A scenario given by file name is relative to the sweep file
+    if let Value::String(name) = &spec.scenario {
+        let scenario_path = path.parent().unwrap_or(Path::new(".")).join(name);
+        let text = fs::read_to_string(&scenario_path)
+            .map_err(|e| format!("{}: {}", scenario_path.display(), e))?;
+        spec.scenario =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", scenario_path.display(), e))?;
+    }
+    Ok(spec)
+}
+
+fn run_sweep(args: &SweepArgs) -> Result<(), String> {
+    let spec = load_sweep(&args.sweep)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads.unwrap_or(0))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let result = pool.install(|| sweep::run(&spec))?;
+
+    fs::create_dir_all(&args.out).map_err(|e| format!("{}: {}", args.out.display(), e))?;
+    write_file(&args.out, "summary.csv", sweep::summary_csv(&result))?;
+    write_file(&args.out, "runs.csv", sweep::runs_csv(&result))?;
+
+    println!(
+        "{} configurations x {} seeds on {} threads -> {}",
+        result.configs.len(),
+        result.runs.len() / result.configs.len().max(1),
+        pool.current_num_threads(),
+        args.out.display(),
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Run(args) => run(args),
+        Command::Sweep(args) => run_sweep(args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("gene-sim: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
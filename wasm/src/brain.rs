@@ -0,0 +1,158 @@
+// Evolvable neural-network controller
+// Tribes can opt out of the hand-tuned steering pipeline and let a small fixed-topology
+// feed-forward network steer instead. Each entity carries BRAIN_WEIGHTS weights that are
+// inherited and mutated on birth like genes, but they live in their own buffer rather than in
+// the gene buffer: the gene registry's layout, genetic distance, statistics and exports are
+// about named traits, and a hundred-odd unnamed weights would swamp every one of them. Births
+// inside the core and births JS reports through register_birth both pass the brain on, so JS
+// only reads the weights (get_brain_ptr).
+
+use crate::rng::Rng;
+
+// Sensors: food (dx, dy, amount), nearest ally (dx, dy), nearest enemy (dx, dy, threat),
+// energy, terrain ahead, bias
+pub const BRAIN_INPUTS: usize = 11;
+pub const BRAIN_HIDDEN: usize = 8;
+// Steering x, y
+pub const BRAIN_OUTPUTS: usize = 2;
+// Hidden weights + hidden biases + output weights + output biases
+pub const BRAIN_WEIGHTS: usize =
+    BRAIN_INPUTS * BRAIN_HIDDEN + BRAIN_HIDDEN + BRAIN_HIDDEN * BRAIN_OUTPUTS + BRAIN_OUTPUTS;
+// Steering magnitude at full output, comparable to the strongest hand-tuned behaviors
+const BRAIN_FORCE: f32 = 4.0;
+// Standard deviation of the weights of a fresh random brain
+pub const INITIAL_WEIGHT_SCALE: f32 = 1.0;
+
+// Sensor readings for one entity; vectors are relative and scaled by vision (0-1)
+#[derive(Default)]
+pub struct BrainInputs {
+    pub food: (f32, f32),
+    pub food_amount: f32,
+    pub ally: (f32, f32),
+    pub enemy: (f32, f32),
+    pub enemy_threat: f32,
+    pub satiation: f32,
+    // 1 if the terrain ahead is impassable
+    pub blocked_ahead: f32,
+}
+
+impl BrainInputs {
+    fn to_array(&self) -> [f32; BRAIN_INPUTS] {
+        [
+            self.food.0,
+            self.food.1,
+            self.food_amount,
+            self.ally.0,
+            self.ally.1,
+            self.enemy.0,
+            self.enemy.1,
+            self.enemy_threat,
+            self.satiation,
+            self.blocked_ahead,
+            1.0,
+        ]
+    }
+}
+
+pub struct Brains {
+    weights: Vec<f32>,
+    // Indexed by tribe id; true if the tribe is brain-controlled
+    neural_tribes: Vec<bool>,
+}
+
+impl Brains {
+    pub fn new(capacity: usize) -> Self {
+        Brains {
+            weights: vec![0.0; capacity * BRAIN_WEIGHTS],
+            neural_tribes: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn controls(&self, tribe: u16) -> bool {
+        self.neural_tribes.get(tribe as usize).copied().unwrap_or(false)
+    }
+
+    pub fn set_tribe_controlled(&mut self, tribe: u16, neural: bool) {
+        let idx = tribe as usize;
+        if idx >= self.neural_tribes.len() {
+            if !neural {
+                return;
+            }
+            self.neural_tribes.resize(idx + 1, false);
+        }
+        self.neural_tribes[idx] = neural;
+    }
+
+    #[inline]
+    pub fn weights(&self, i: usize) -> &[f32] {
+        &self.weights[i * BRAIN_WEIGHTS..(i + 1) * BRAIN_WEIGHTS]
+    }
+
+    #[inline]
+    pub fn weights_mut(&mut self, i: usize) -> &mut [f32] {
+        &mut self.weights[i * BRAIN_WEIGHTS..(i + 1) * BRAIN_WEIGHTS]
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.weights
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    // All-zero weights: a slot that was never given a brain (it would never steer)
+    pub fn is_blank(&self, i: usize) -> bool {
+        self.weights(i).iter().all(|&w| w == 0.0)
+    }
+
+    // Fresh random brain with weights ~ N(0, scale)
+    pub fn randomize(&mut self, i: usize, scale: f32, rng: &mut Rng) {
+        for w in self.weights_mut(i) {
+            *w = rng.next_gaussian() * scale;
+        }
+    }
+
+    pub fn copy(&mut self, from: usize, to: usize) {
+        if from != to {
+            self.weights
+                .copy_within(from * BRAIN_WEIGHTS..(from + 1) * BRAIN_WEIGHTS, to * BRAIN_WEIGHTS);
+        }
+    }
+
+    // Perturb each weight with probability `rate` by N(0, scale)
+    pub fn mutate(&mut self, i: usize, rate: f32, scale: f32, rng: &mut Rng) {
+        for w in self.weights_mut(i) {
+            if rng.next_f32() < rate {
+                *w += rng.next_gaussian() * scale;
+            }
+        }
+    }
+
+    // Forward pass: tanh hidden layer, tanh outputs scaled to a steering force
+    pub fn think(&self, i: usize, inputs: &BrainInputs) -> (f32, f32) {
+        let w = self.weights(i);
+        let input = inputs.to_array();
+
+        let (hidden_w, rest) = w.split_at(BRAIN_INPUTS * BRAIN_HIDDEN);
+        let (hidden_b, rest) = rest.split_at(BRAIN_HIDDEN);
+        let (output_w, output_b) = rest.split_at(BRAIN_HIDDEN * BRAIN_OUTPUTS);
+
+        let mut hidden = [0.0f32; BRAIN_HIDDEN];
+        for (h, value) in hidden.iter_mut().enumerate() {
+            let row = &hidden_w[h * BRAIN_INPUTS..(h + 1) * BRAIN_INPUTS];
+            let sum: f32 = row.iter().zip(&input).map(|(w, x)| w * x).sum();
+            *value = (sum + hidden_b[h]).tanh();
+        }
+
+        let mut output = [0.0f32; BRAIN_OUTPUTS];
+        for (o, value) in output.iter_mut().enumerate() {
+            let row = &output_w[o * BRAIN_HIDDEN..(o + 1) * BRAIN_HIDDEN];
+            let sum: f32 = row.iter().zip(&hidden).map(|(w, h)| w * h).sum();
+            *value = (sum + output_b[o]).tanh();
+        }
+
+        (output[0] * BRAIN_FORCE, output[1] * BRAIN_FORCE)
+    }
+}
@@ -3,9 +3,19 @@
 
 use wasm_bindgen::prelude::*;
 
+// Biome indices match the BiomeType order used by the JS biome grid
+const BIOME_COUNT: usize = 6;
+
+// Default vision opacity per biome cell crossed:
+// ocean, mountain, forest, grassland, desert, savanna
+const DEFAULT_BIOME_OPACITY: [f32; BIOME_COUNT] = [0.0, 2.0, 0.35, 0.0, 0.0, 0.05];
+
 #[wasm_bindgen]
 pub struct BiomeCollisionMap {
     traversability: Vec<u8>,
+    // Optional biome type per cell (empty until provided) and its vision opacity
+    biome_types: Vec<u8>,
+    biome_opacity: [f32; BIOME_COUNT],
     grid_width: usize,
     grid_height: usize,
     cell_size: f32,
@@ -36,6 +46,8 @@ impl BiomeCollisionMap {
         
         BiomeCollisionMap {
             traversability,
+            biome_types: Vec::new(),
+            biome_opacity: DEFAULT_BIOME_OPACITY,
             grid_width,
             grid_height,
             cell_size,
@@ -106,71 +118,173 @@ impl BiomeCollisionMap {
             self.clear_cache();
         }
     }
-}
-
-// Apply collision detection and boundary avoidance
-pub fn apply_collision_constraints(
-    pos_x: &mut [f32],
-    pos_y: &mut [f32],
-    vel_x: &mut [f32],
-    vel_y: &mut [f32],
-    collision_map: &mut BiomeCollisionMap,
-    dt: f32,
-) {
-    for i in 0..pos_x.len() {
-        let current_x = pos_x[i];
-        let current_y = pos_y[i];
-        let vx = vel_x[i];
-        let vy = vel_y[i];
-        
-        // Calculate next position
-        let next_x = current_x + vx * dt;
-        let next_y = current_y + vy * dt;
-        
-        // Flip Y for texture coordinate system
-        let flipped_y = collision_map.world_height - next_y;
-        
-        // Check if next position is valid
-        if collision_map.is_traversable(next_x, flipped_y) {
-            // Move is valid
-            pos_x[i] = next_x;
-            pos_y[i] = next_y;
+    
+    // Provide per-cell biome types (0-5) so terrain can occlude vision
+    pub fn set_biome_types(&mut self, biome_types: &[u8]) {
+        if biome_types.len() == self.traversability.len() {
+            self.biome_types = biome_types.to_vec();
+        }
+    }
+    
+    // Opacity accumulated per cell of this biome a sight line crosses (>= 1.0 total blocks)
+    pub fn set_biome_opacity(&mut self, biome: u8, opacity: f32) {
+        if let Some(slot) = self.biome_opacity.get_mut(biome as usize) {
+            *slot = opacity.max(0.0);
+        }
+    }
+    
+    // Grid raycast (DDA) between two world points along the shortest wrapped direction;
+    // false if terrain blocks the view
+    pub fn line_of_sight(&self, from_x: f32, from_y: f32, to_x: f32, to_y: f32) -> bool {
+        if self.biome_types.is_empty() {
+            return true;
+        }
+        
+        // Walk toward the target's nearest image; cells are wrapped when sampled below
+        let (wrap_dx, wrap_dy) = self.world_delta(from_x, from_y, to_x, to_y);
+        let to_x = from_x + wrap_dx;
+        let to_y = from_y + wrap_dy;
+        
+        // Continuous grid coordinates (Y flipped for texture coordinates)
+        let gx0 = from_x / self.cell_size;
+        let gy0 = (self.world_height - from_y) / self.cell_size;
+        let gx1 = to_x / self.cell_size;
+        let gy1 = (self.world_height - to_y) / self.cell_size;
+        let dx = gx1 - gx0;
+        let dy = gy1 - gy0;
+        let ray_len = (dx * dx + dy * dy).sqrt();
+        
+        let mut cx = gx0.floor() as i32;
+        let mut cy = gy0.floor() as i32;
+        let start = (cx, cy);
+        let step_x = if dx > 0.0 { 1 } else { -1 };
+        let step_y = if dy > 0.0 { 1 } else { -1 };
+        
+        // Ray parameter (0..1) needed to cross one cell on each axis, and to the next boundary
+        let t_delta_x = if dx != 0.0 { 1.0 / dx.abs() } else { f32::INFINITY };
+        let t_delta_y = if dy != 0.0 { 1.0 / dy.abs() } else { f32::INFINITY };
+        let mut t_max_x = if dx > 0.0 {
+            (cx as f32 + 1.0 - gx0) / dx
+        } else if dx < 0.0 {
+            (gx0 - cx as f32) / -dx
         } else {
-            // Collision detected - try sliding along axes
-            let flipped_current_y = collision_map.world_height - current_y;
+            f32::INFINITY
+        };
+        let mut t_max_y = if dy > 0.0 {
+            (cy as f32 + 1.0 - gy0) / dy
+        } else if dy < 0.0 {
+            (gy0 - cy as f32) / -dy
+        } else {
+            f32::INFINITY
+        };
+        
+        let mut t = 0.0;
+        let mut accumulated = 0.0;
+        
+        loop {
+            let t_next = t_max_x.min(t_max_y).min(1.0);
             
-            // Try horizontal movement only
-            if collision_map.is_traversable(next_x, flipped_current_y) {
-                pos_x[i] = next_x;
-                vel_y[i] *= -0.5; // Bounce vertically
-            }
-            // Try vertical movement only
-            else if collision_map.is_traversable(current_x, flipped_y) {
-                pos_y[i] = next_y;
-                vel_x[i] *= -0.5; // Bounce horizontally
-            }
-            // Both blocked - reverse direction
-            else {
-                vel_x[i] *= -0.8;
-                vel_y[i] *= -0.8;
-                
-                // Try to find escape direction
-                let angles = [0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0];
-                let escape_dist = collision_map.cell_size * 2.0;
+            // The observer's own cell never occludes
+            if (cx, cy) != start {
+                let gx = cx.rem_euclid(self.grid_width as i32) as usize;
+                let gy = cy.rem_euclid(self.grid_height as i32) as usize;
+                let biome = self.biome_types[gy * self.grid_width + gx] as usize;
+                let opacity = self.biome_opacity.get(biome).copied().unwrap_or(0.0);
                 
-                for angle_deg in &angles {
-                    let angle = angle_deg * std::f32::consts::PI / 180.0;
-                    let test_x = current_x + angle.cos() * escape_dist;
-                    let test_y = current_y + angle.sin() * escape_dist;
-                    let test_flipped_y = collision_map.world_height - test_y;
-                    
-                    if collision_map.is_traversable(test_x, test_flipped_y) {
-                        vel_x[i] = angle.cos() * 30.0;
-                        vel_y[i] = angle.sin() * 30.0;
-                        break;
-                    }
+                // Weight by the length of the ray inside this cell
+                accumulated += opacity * (t_next - t) * ray_len;
+                if accumulated >= 1.0 {
+                    return false;
                 }
             }
+            
+            if t_next >= 1.0 {
+                return true;
+            }
+            
+            t = t_next;
+            if t_max_x < t_max_y {
+                cx += step_x;
+                t_max_x += t_delta_x;
+            } else {
+                cy += step_y;
+                t_max_y += t_delta_y;
+            }
+        }
+    }
+}
+
+// Grid-level accessors used by pathfinding (not exposed to JS)
+impl BiomeCollisionMap {
+    #[inline]
+    pub fn grid_width(&self) -> usize {
+        self.grid_width
+    }
+
+    #[inline]
+    pub fn grid_height(&self) -> usize {
+        self.grid_height
+    }
+
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    // Bumped whenever traversability changes so derived data can be invalidated
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.cache_generation
+    }
+
+    // Convert world coordinates to a grid cell (Y flipped for texture coordinates)
+    #[inline]
+    pub fn world_to_cell(&self, world_x: f32, world_y: f32) -> (usize, usize) {
+        let wrapped_x = ((world_x % self.world_width) + self.world_width) % self.world_width;
+        let flipped_y = self.world_height - world_y;
+        let wrapped_y = ((flipped_y % self.world_height) + self.world_height) % self.world_height;
+
+        let cx = ((wrapped_x / self.cell_size) as usize).min(self.grid_width - 1);
+        let cy = ((wrapped_y / self.cell_size) as usize).min(self.grid_height - 1);
+        (cx, cy)
+    }
+
+    // World-space center of a grid cell (inverse of world_to_cell)
+    #[inline]
+    pub fn cell_to_world(&self, cx: usize, cy: usize) -> (f32, f32) {
+        let x = (cx as f32 + 0.5) * self.cell_size;
+        let y = self.world_height - (cy as f32 + 0.5) * self.cell_size;
+        (x, y)
+    }
+
+    // Shortest offset from one world point to another on the wrapping world
+    #[inline]
+    pub fn world_delta(&self, from_x: f32, from_y: f32, to_x: f32, to_y: f32) -> (f32, f32) {
+        let wrap = |d: f32, size: f32| d - (d / size).round() * size;
+        (wrap(to_x - from_x, self.world_width), wrap(to_y - from_y, self.world_height))
+    }
+
+    // Biome type (0-5) at a world position, if biome types were provided
+    #[inline]
+    pub fn biome_at(&self, world_x: f32, world_y: f32) -> Option<u8> {
+        if self.biome_types.is_empty() {
+            return None;
         }
+        let (cx, cy) = self.world_to_cell(world_x, world_y);
+        Some(self.biome_types[cy * self.grid_width + cx])
     }
-}
\ No newline at end of file
+
+    // Uncached lookup by world position, safe to call through a shared reference
+    #[inline]
+    pub fn is_world_traversable(&self, world_x: f32, world_y: f32) -> bool {
+        let (cx, cy) = self.world_to_cell(world_x, world_y);
+        self.is_cell_traversable(cx, cy)
+    }
+
+    // Uncached lookup by cell index, safe to call through a shared reference
+    #[inline]
+    pub fn is_cell_traversable(&self, cx: usize, cy: usize) -> bool {
+        cx < self.grid_width && cy < self.grid_height
+            && self.traversability[cy * self.grid_width + cx] == 1
+    }
+}
@@ -0,0 +1,178 @@
+// Combat resolution: predators striking their prey and territorial clashes between tribes
+// Pack hunters (see movement.rs) get damage and success multipliers of 1 + pack_bonus × 0.5,
+// and every fight scales with group support = nearby allies / max(1, nearby enemies).
+// Body size scales reach, damage dealt and the energy a corpse yields.
+
+use crate::genes::Genes;
+use crate::rng::Rng;
+
+const BASE_DAMAGE: f32 = 8.0;
+const CARNIVORE_DAMAGE_MULT: f32 = 1.5;
+const CORPSE_ENERGY_RATIO: f32 = 0.75;
+const MAX_CORPSE_ENERGY: f32 = 30.0;
+// Attack attempts per second at fight chance 1.0
+const ATTACK_RATE: f32 = 6.0;
+const ENERGY_MAX: f32 = 100.0;
+// Below this aggression an entity never starts a territorial fight
+const TERRITORIAL_MIN_AGGRESSION: f32 = 0.3;
+// Share of the attacker's damage the defender returns, scaled by its own aggression
+const RETALIATION: f32 = 0.6;
+
+// Read-only per-entity state written by the movement pass
+pub struct CombatEnv<'a> {
+    pub pos_x: &'a [f32],
+    pub pos_y: &'a [f32],
+    pub alive: &'a [u8],
+    pub genes: &'a Genes,
+    pub hunt_target: &'a [i32],
+    pub pack_bonus: &'a [f32],
+    pub crowd_stress: &'a [f32],
+    pub nearby_allies: &'a [u16],
+    pub nearby_enemies: &'a [u16],
+    pub enemy_strength: &'a [f32],
+    pub rival: &'a [i32],
+}
+
+impl CombatEnv<'_> {
+    #[inline]
+    fn in_reach(&self, i: usize, j: usize) -> bool {
+        let dx = self.pos_x[j] - self.pos_x[i];
+        let dy = self.pos_y[j] - self.pos_y[i];
+        // Within the pair's combined personal space (20 units at size 1.0)
+        let reach = (self.genes.radius(i) + self.genes.radius(j)) * 0.5;
+        dx * dx + dy * dy <= reach * reach
+    }
+
+    // Success multiplier from numbers: min(2, 1 + groupSupport × 0.2)
+    #[inline]
+    pub fn group_multiplier(&self, i: usize) -> f32 {
+        let allies = self.nearby_allies[i] as f32;
+        let enemies = (self.nearby_enemies[i] as f32).max(1.0);
+        (1.0 + allies / enemies * 0.2).min(2.0)
+    }
+}
+
+// Let hunter `i` strike its current target if in reach; returns the victim if the target died
+pub fn resolve_predation(
+    i: usize,
+    env: &CombatEnv,
+    energy: &mut [f32],
+    rng: &mut Rng,
+    dt: f32,
+) -> Option<usize> {
+    let target = usize::try_from(env.hunt_target[i]).ok()?;
+    if target == i || env.alive[target] == 0 || !env.in_reach(i, target) {
+        return None;
+    }
+
+    let my_energy = energy[i];
+    let aggression = env.genes.aggression(i);
+    let carnivore_level = env.genes.diet(i).max(0.0);
+
+    // Well-fed hunters stop attacking
+    let satiation = (my_energy / ENERGY_MAX).clamp(0.0, 1.0);
+    if satiation >= 0.8 {
+        return None;
+    }
+
+    let hunting_threshold = 95.0 - carnivore_level * 35.0;
+    let hunger_desperation = ((hunting_threshold - my_energy) / hunting_threshold).max(0.0);
+    let pack_multiplier = 1.0 + env.pack_bonus[i] * 0.5;
+    let group_multiplier = env.group_multiplier(i);
+
+    // Hunger-modified aggression drives both the chance and the force of the attack
+    let drive = aggression * (1.0 - satiation * 0.7);
+    let fight_chance = (drive + carnivore_level * 0.3 + hunger_desperation * 0.3)
+        * (0.2 + (1.0 - satiation) * 0.15 + env.crowd_stress[i] * 0.1)
+        * group_multiplier
+        * pack_multiplier;
+
+    if rng.next_f32() >= (fight_chance * ATTACK_RATE * dt).min(1.0) {
+        return None;
+    }
+
+    let carnivore_bonus = 1.0 + carnivore_level * (CARNIVORE_DAMAGE_MULT - 1.0);
+    let damage =
+        BASE_DAMAGE * (0.5 + drive) * carnivore_bonus * pack_multiplier * env.genes.size(i);
+    let target_energy = energy[target];
+    energy[target] -= damage;
+
+    // Bite-based energy drain
+    let stolen = (damage * carnivore_level * 0.5).min(target_energy * 0.3);
+    energy[i] += stolen;
+
+    let kill = if energy[target] <= 0.0 {
+        // Larger prey leave more to eat
+        let prey_size = env.genes.size(target);
+        let corpse = (target_energy * CORPSE_ENERGY_RATIO * prey_size)
+            .min(MAX_CORPSE_ENERGY * prey_size)
+            * carnivore_level;
+        energy[i] += corpse;
+        Some(target)
+    } else {
+        None
+    };
+
+    energy[i] = energy[i].min(ENERGY_MAX);
+    kill
+}
+
+// Non-predatory clash between `i` and its nearest rival from another tribe.
+// Aggression picks the fight, group support and energy decide who takes the damage.
+// Returns the rival if it died; the attacker can also be left at or below zero energy.
+pub fn resolve_territorial(
+    i: usize,
+    env: &CombatEnv,
+    energy: &mut [f32],
+    rng: &mut Rng,
+    dt: f32,
+) -> Option<usize> {
+    let rival = usize::try_from(env.rival[i]).ok()?;
+    // Prey is handled by resolve_predation
+    if rival == i || env.alive[rival] == 0 || env.hunt_target[i] == rival as i32 {
+        return None;
+    }
+
+    let aggression = env.genes.aggression(i);
+    if aggression < TERRITORIAL_MIN_AGGRESSION || !env.in_reach(i, rival) {
+        return None;
+    }
+
+    let my_energy = energy[i].max(0.0);
+    let their_energy = energy[rival].max(0.0);
+    let my_group = env.group_multiplier(i);
+    let their_group = env.group_multiplier(rival);
+
+    // Confidence: own energy and numbers against the rival's, damped by the enemy strength in sight
+    let my_size = env.genes.size(i);
+    let their_size = env.genes.size(rival);
+    let my_power = my_energy * my_group * my_size;
+    let their_power = their_energy * their_group * their_size;
+    let odds = my_power / (my_power + their_power + 1.0);
+    let intimidation = 1.0 / (1.0 + env.enemy_strength[i] * 0.25);
+
+    let fight_chance = (aggression - TERRITORIAL_MIN_AGGRESSION)
+        * (0.2 + env.crowd_stress[i] * 0.15)
+        * odds
+        * 2.0
+        * intimidation
+        * my_group;
+
+    if rng.next_f32() >= (fight_chance * ATTACK_RATE * dt).min(1.0) {
+        return None;
+    }
+
+    // Both sides trade blows weighted by their share of the combined power
+    let damage = BASE_DAMAGE * (0.5 + aggression) * my_group * my_size * odds * 2.0;
+    let retaliation = BASE_DAMAGE
+        * RETALIATION
+        * (0.5 + env.genes.aggression(rival))
+        * their_group
+        * their_size
+        * (1.0 - odds)
+        * 2.0;
+    energy[rival] -= damage;
+    energy[i] -= retaliation;
+
+    (energy[rival] <= 0.0).then_some(rival)
+}
@@ -0,0 +1,118 @@
+// Energy accounting for the WASM path
+//
+// Energy Loss = (BaseCost + MovementCost) × AgeFactor, per second, where
+//   BaseCost     = metabolism × base_cost × DietFactor × size^0.75
+//   MovementCost = speed² × movement_cost × metabolism × DietEfficiency × size
+//   DietFactor     = 1 + herbivore × herbivore_base_penalty - carnivore × carnivore_base_discount
+//   DietEfficiency = 1 - carnivore × carnivore_movement_discount
+// The diet modifier from BEHAVIOR_SYSTEM.md is folded into the two diet terms above.
+
+use serde::{Deserialize, Serialize};
+
+use crate::genes::Genes;
+use crate::types::DeathCause;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyConfig {
+    pub base_cost: f32,
+    pub movement_cost: f32,
+    pub herbivore_base_penalty: f32,
+    pub carnivore_base_discount: f32,
+    pub carnivore_movement_discount: f32,
+    // Fraction of max_age after which upkeep starts rising
+    pub age_onset: f32,
+    // Extra upkeep multiplier reached at max_age
+    pub age_penalty: f32,
+    pub max_age: f32,
+    pub max_energy: f32,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        EnergyConfig {
+            base_cost: 1.5,
+            movement_cost: 0.000005,
+            herbivore_base_penalty: 0.5,
+            carnivore_base_discount: 0.3,
+            carnivore_movement_discount: 0.5,
+            age_onset: 0.5,
+            age_penalty: 1.0,
+            max_age: 80.0,
+            max_energy: 100.0,
+        }
+    }
+}
+
+impl EnergyConfig {
+    // Energy drained per second for the given traits and current speed
+    #[inline]
+    pub fn drain_rate(&self, metabolism: f32, diet: f32, size: f32, speed: f32, age: f32) -> f32 {
+        let carnivore = diet.max(0.0);
+        let herbivore = (-diet).max(0.0);
+
+        let diet_factor =
+            1.0 + herbivore * self.herbivore_base_penalty - carnivore * self.carnivore_base_discount;
+        let diet_efficiency = 1.0 - carnivore * self.carnivore_movement_discount;
+
+        // Allometric upkeep: resting cost follows Kleiber's law, moving mass costs linearly
+        let base = metabolism * self.base_cost * diet_factor * size.powf(0.75);
+        let movement = speed * speed * self.movement_cost * metabolism * diet_efficiency * size;
+
+        // Upkeep grows linearly from age_onset to max_age
+        let onset = self.max_age * self.age_onset;
+        let age_factor = if age > onset && self.max_age > onset {
+            1.0 + ((age - onset) / (self.max_age - onset)).min(1.0) * self.age_penalty
+        } else {
+            1.0
+        };
+
+        (base + movement).max(0.0) * age_factor
+    }
+}
+
+// Energy from one unit of plant food (as in simulationCore.ts): full value for herbivores,
+// falling off with carnivory down to 30%
+#[inline]
+pub fn plant_energy(consumed: f32, diet: f32) -> f32 {
+    let efficiency = if diet < 0.0 { 1.0 } else { (1.0 - diet.abs()).max(0.3) };
+    (consumed * 8.0 * efficiency).min(30.0)
+}
+
+// Age entities and drain energy for a slice of entities; returns the deaths as (index, cause)
+#[allow(clippy::too_many_arguments)]
+pub fn update_batch(
+    energy: &mut [f32],
+    age: &mut [f32],
+    alive: &[u8],
+    vel_x: &[f32],
+    vel_y: &[f32],
+    genes: &Genes,
+    start_idx: usize,
+    end_idx: usize,
+    config: &EnergyConfig,
+    dt: f32,
+    deaths: &mut Vec<(usize, DeathCause)>,
+) {
+    for i in start_idx..end_idx {
+        if alive[i] == 0 {
+            continue;
+        }
+
+        age[i] += dt;
+
+        let metabolism = genes.metabolism(i);
+        let diet = genes.diet(i);
+        let size = genes.size(i);
+        let speed = (vel_x[i] * vel_x[i] + vel_y[i] * vel_y[i]).sqrt();
+
+        energy[i] -= config.drain_rate(metabolism, diet, size, speed, age[i]) * dt;
+        energy[i] = energy[i].min(config.max_energy);
+
+        if energy[i] <= 0.0 {
+            deaths.push((i, DeathCause::Starved));
+        } else if age[i] > config.max_age {
+            deaths.push((i, DeathCause::OldAge));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// Typed simulation events, queued as they happen and drained by JS as a binary buffer
+// (decoded by src/sim/simEvents.ts). The queue is off by default; while on it holds at most
+// MAX_QUEUED events between drains and counts the rest as dropped.
+//
+// "EVT1", u32 event count, u32 dropped count, then per event (little endian):
+// kind u8, tick u32, followed by
+//   0 Birth          child u64, parent_a u64, parent_b u64 (0 = none), slot u32, tribe u16
+//   1 Death          id u64, slot u32, cause u8 (DeathCause), x f32, y f32
+//   2 Kill           killer u64, victim u64, energy gained f32, killer_slot u32, victim_slot u32,
+//                    x f32, y f32 (victim position)
+//   3 BiomeEnter     id u64, slot u32, biome u8, from u8
+//   4 SpeciesFormed  species u32, members u32
+
+use crate::types::DeathCause;
+
+const BINARY_MAGIC: &[u8; 4] = b"EVT1";
+const MAX_QUEUED: usize = 65_536;
+
+// Biome code for a slot whose biome hasn't been observed yet
+pub const NO_BIOME: u8 = u8::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    Birth { child: u64, parents: [u64; 2], slot: u32, tribe: u16 },
+    Death { id: u64, slot: u32, cause: DeathCause, x: f32, y: f32 },
+    Kill { killer: u64, victim: u64, energy: f32, killer_slot: u32, victim_slot: u32, x: f32, y: f32 },
+    BiomeEnter { id: u64, slot: u32, biome: u8, from: u8 },
+    SpeciesFormed { species: u32, members: u32 },
+}
+
+impl Event {
+    fn kind(&self) -> u8 {
+        match self {
+            Event::Birth { .. } => 0,
+            Event::Death { .. } => 1,
+            Event::Kill { .. } => 2,
+            Event::BiomeEnter { .. } => 3,
+            Event::SpeciesFormed { .. } => 4,
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match *self {
+            Event::Birth { child, parents, slot, tribe } => {
+                out.extend_from_slice(&child.to_le_bytes());
+                out.extend_from_slice(&parents[0].to_le_bytes());
+                out.extend_from_slice(&parents[1].to_le_bytes());
+                out.extend_from_slice(&slot.to_le_bytes());
+                out.extend_from_slice(&tribe.to_le_bytes());
+            }
+            Event::Death { id, slot, cause, x, y } => {
+                out.extend_from_slice(&id.to_le_bytes());
+                out.extend_from_slice(&slot.to_le_bytes());
+                out.push(cause as u8);
+                out.extend_from_slice(&x.to_le_bytes());
+                out.extend_from_slice(&y.to_le_bytes());
+            }
+            Event::Kill { killer, victim, energy, killer_slot, victim_slot, x, y } => {
+                out.extend_from_slice(&killer.to_le_bytes());
+                out.extend_from_slice(&victim.to_le_bytes());
+                out.extend_from_slice(&energy.to_le_bytes());
+                out.extend_from_slice(&killer_slot.to_le_bytes());
+                out.extend_from_slice(&victim_slot.to_le_bytes());
+                out.extend_from_slice(&x.to_le_bytes());
+                out.extend_from_slice(&y.to_le_bytes());
+            }
+            Event::BiomeEnter { id, slot, biome, from } => {
+                out.extend_from_slice(&id.to_le_bytes());
+                out.extend_from_slice(&slot.to_le_bytes());
+                out.push(biome);
+                out.push(from);
+            }
+            Event::SpeciesFormed { species, members } => {
+                out.extend_from_slice(&species.to_le_bytes());
+                out.extend_from_slice(&members.to_le_bytes());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventQueue {
+    enabled: bool,
+    events: Vec<(u32, Event)>,
+    dropped: u32,
+}
+
+impl EventQueue {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Turning the queue off discards anything not yet drained
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.events.clear();
+            self.dropped = 0;
+        }
+    }
+
+    pub fn push(&mut self, tick: u64, event: Event) {
+        if !self.enabled {
+            return;
+        }
+        if self.events.len() < MAX_QUEUED {
+            self.events.push((tick as u32, event));
+        } else {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    // Encode and empty the queue
+    pub fn drain(&mut self) -> Vec<u8> {
+        // Largest record (Kill) is 1 + 4 + 36 bytes
+        let mut out = Vec::with_capacity(12 + self.events.len() * 41);
+        out.extend_from_slice(BINARY_MAGIC);
+        out.extend_from_slice(&(self.events.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.dropped.to_le_bytes());
+
+        for (tick, event) in self.events.drain(..) {
+            out.push(event.kind());
+            out.extend_from_slice(&tick.to_le_bytes());
+            event.write(&mut out);
+        }
+        self.dropped = 0;
+
+        out
+    }
+}
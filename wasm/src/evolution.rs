@@ -0,0 +1,300 @@
+// Genetic diversity and selection metrics over the gene buffer
+// Diversity is measured on the living population at query time:
+//   meanDistance  mean normalized genetic distance over member pairs (a sample of at most
+//                 DIVERSITY_SAMPLE members per group, spread evenly over the slots)
+//   entropy       Shannon entropy of each gene over ENTROPY_BINS bins of its range, scaled
+//                 to 0..1 (0 = everyone in one bin, 1 = spread evenly)
+// Selection is measured on births since the last reset:
+//   selection     S = mean gene of the parents of those births − mean of the population they
+//                 were drawn from (each parent counted once per birth). The population mean is
+//                 the one at the time of the birth, refreshed every BASELINE_WINDOW ticks, so
+//                 later responses to selection don't cancel S out.
+//   heritability  h² = slope of the offspring-on-midparent regression (two-parent births)
+//   clonalHeritability  slope of offspring on parent for births with a single known parent;
+//                 for clonal reproduction this is broad-sense H², not h², so it is kept apart
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::genes::{GeneRegistry, Genes};
+use crate::stats::{self, Welford};
+
+const DIVERSITY_SAMPLE: usize = 256;
+const ENTROPY_BINS: usize = 16;
+// Parent–offspring pairs needed before a heritability estimate is reported
+const MIN_HERITABILITY_PAIRS: u32 = 10;
+// Ticks a population-mean baseline for the selection differential stays valid
+const BASELINE_WINDOW: u64 = 30;
+
+// Running sums for a least-squares regression of y on x
+#[derive(Debug, Clone, Copy, Default)]
+struct Regression {
+    n: u32,
+    sx: f64,
+    sy: f64,
+    sxx: f64,
+    sxy: f64,
+}
+
+impl Regression {
+    fn push(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        self.sx += x;
+        self.sy += y;
+        self.sxx += x * x;
+        self.sxy += x * y;
+    }
+
+    fn slope(&self) -> Option<f64> {
+        let n = self.n as f64;
+        let var_x = self.sxx - self.sx * self.sx / n;
+        if self.n < MIN_HERITABILITY_PAIRS || var_x <= f64::EPSILON {
+            return None;
+        }
+        Some((self.sxy - self.sx * self.sy / n) / var_x)
+    }
+}
+
+// Birth records for one tribe (or everyone)
+#[derive(Debug, Clone)]
+struct BirthRecords {
+    births: u32,
+    parents: u32,
+    parent_sums: Vec<f64>,
+    // Population mean at each birth, summed once per parent like parent_sums
+    baseline_sums: Vec<f64>,
+    // Offspring on midparent (two parents) and on the lone parent (one parent)
+    regressions: Vec<Regression>,
+    clonal_regressions: Vec<Regression>,
+}
+
+impl BirthRecords {
+    fn new(stride: usize) -> Self {
+        BirthRecords {
+            births: 0,
+            parents: 0,
+            parent_sums: vec![0.0; stride],
+            baseline_sums: vec![0.0; stride],
+            regressions: vec![Regression::default(); stride],
+            clonal_regressions: vec![Regression::default(); stride],
+        }
+    }
+
+    fn record(&mut self, parents: &[&[f32]], child: &[f32], baseline: &[f64]) {
+        self.births += 1;
+        self.parents += parents.len() as u32;
+        let share = 1.0 / parents.len() as f64;
+        let regressions = if parents.len() == 1 {
+            &mut self.clonal_regressions
+        } else {
+            &mut self.regressions
+        };
+        for (g, &value) in child.iter().enumerate() {
+            let mut midparent = 0.0;
+            for parent in parents {
+                self.parent_sums[g] += parent[g] as f64;
+                midparent += parent[g] as f64 * share;
+            }
+            self.baseline_sums[g] += baseline[g] * parents.len() as f64;
+            regressions[g].push(midparent, value as f64);
+        }
+    }
+}
+
+// Parent and offspring genes of every birth since the last reset
+pub struct SelectionTracker {
+    stride: usize,
+    by_tribe: BTreeMap<u16, BirthRecords>,
+    global: BirthRecords,
+    // Population gene means births are compared against, and the tick they were taken
+    tribe_baseline: BTreeMap<u16, Vec<f64>>,
+    global_baseline: Vec<f64>,
+    baseline_tick: Option<u64>,
+}
+
+impl SelectionTracker {
+    pub fn new(stride: usize) -> Self {
+        SelectionTracker {
+            stride,
+            by_tribe: BTreeMap::new(),
+            global: BirthRecords::new(stride),
+            tribe_baseline: BTreeMap::new(),
+            global_baseline: vec![0.0; stride],
+            baseline_tick: None,
+        }
+    }
+
+    // Whether the population means are missing or older than BASELINE_WINDOW ticks
+    pub fn baseline_due(&self, tick: u64) -> bool {
+        self.baseline_tick.is_none_or(|t| tick >= t + BASELINE_WINDOW)
+    }
+
+    pub fn set_baseline(&mut self, alive: &[u8], tribe_id: &[u16], genes: &Genes, count: usize, tick: u64) {
+        let (by_tribe, global) = stats::accumulate(alive, tribe_id, genes, count);
+        let means = |acc: &[Welford]| acc.iter().map(|w| w.mean).collect::<Vec<f64>>();
+        self.tribe_baseline = by_tribe.iter().map(|(&t, acc)| (t, means(acc))).collect();
+        self.global_baseline = means(&global);
+        self.baseline_tick = Some(tick);
+    }
+
+    // A birth into `tribe`; parents without genes (unknown slots) are left out
+    pub fn record(&mut self, tribe: u16, parents: &[&[f32]], child: &[f32]) {
+        if parents.is_empty() {
+            return;
+        }
+        let stride = self.stride;
+        let tribe_baseline = self.tribe_baseline.get(&tribe).unwrap_or(&self.global_baseline);
+        self.by_tribe
+            .entry(tribe)
+            .or_insert_with(|| BirthRecords::new(stride))
+            .record(parents, child, tribe_baseline);
+        self.global.record(parents, child, &self.global_baseline);
+    }
+
+    pub fn reset(&mut self) {
+        self.by_tribe.clear();
+        self.global = BirthRecords::new(self.stride);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvolutionStats {
+    pub count: u32,
+    pub mean_distance: f32,
+    pub entropy: BTreeMap<String, f32>,
+    pub mean_entropy: f32,
+    // Births and parent contributions behind the selection/heritability estimates
+    pub births: u32,
+    pub parents: u32,
+    pub selection: BTreeMap<String, f32>,
+    pub heritability: BTreeMap<String, f32>,
+    pub clonal_heritability: BTreeMap<String, f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvolutionReport {
+    pub by_tribe: BTreeMap<String, EvolutionStats>,
+    pub global: EvolutionStats,
+}
+
+// Mean normalized distance over all pairs of an evenly spaced sample of `members`
+pub fn mean_pairwise_distance(members: &[usize], genes: &Genes, registry: &GeneRegistry) -> f32 {
+    let step = members.len().div_ceil(DIVERSITY_SAMPLE).max(1);
+    let sample: Vec<&[f32]> = members.iter().step_by(step).map(|&i| genes.entity(i)).collect();
+
+    let mut total = 0.0f64;
+    let mut pairs = 0u64;
+    for (k, a) in sample.iter().enumerate() {
+        for b in &sample[k + 1..] {
+            total += registry.distance(a, b) as f64;
+            pairs += 1;
+        }
+    }
+    if pairs == 0 {
+        0.0
+    } else {
+        (total / pairs as f64) as f32
+    }
+}
+
+// Normalized Shannon entropy of every gene over the members
+fn gene_entropy(members: &[usize], genes: &Genes, registry: &GeneRegistry) -> Vec<f32> {
+    let n = members.len() as f64;
+    let max_entropy = (ENTROPY_BINS as f64).ln();
+    registry
+        .specs()
+        .iter()
+        .enumerate()
+        .map(|(g, spec)| {
+            if members.is_empty() {
+                return 0.0;
+            }
+            let mut bins = [0u32; ENTROPY_BINS];
+            for &i in members {
+                bins[stats::bin_of(genes.get(i, g), spec.min, spec.max, ENTROPY_BINS)] += 1;
+            }
+            let h: f64 = bins
+                .iter()
+                .filter(|&&c| c > 0)
+                .map(|&c| {
+                    let p = c as f64 / n;
+                    -p * p.ln()
+                })
+                .sum();
+            (h / max_entropy) as f32
+        })
+        .collect()
+}
+
+fn group_stats(
+    members: &[usize],
+    records: Option<&BirthRecords>,
+    genes: &Genes,
+    registry: &GeneRegistry,
+) -> EvolutionStats {
+    let entropy = gene_entropy(members, genes, registry);
+    let mut out = EvolutionStats {
+        count: members.len() as u32,
+        mean_distance: mean_pairwise_distance(members, genes, registry),
+        mean_entropy: entropy.iter().sum::<f32>() / entropy.len().max(1) as f32,
+        ..Default::default()
+    };
+
+    for (spec, &h) in registry.specs().iter().zip(&entropy) {
+        out.entropy.insert(spec.name.clone(), h);
+    }
+
+    if let Some(records) = records {
+        out.births = records.births;
+        out.parents = records.parents;
+        for (g, spec) in registry.specs().iter().enumerate() {
+            if records.parents > 0 {
+                let n = records.parents as f64;
+                let s = (records.parent_sums[g] - records.baseline_sums[g]) / n;
+                out.selection.insert(spec.name.clone(), s as f32);
+            }
+            if let Some(h2) = records.regressions[g].slope() {
+                out.heritability.insert(spec.name.clone(), h2 as f32);
+            }
+            if let Some(h2) = records.clonal_regressions[g].slope() {
+                out.clonal_heritability.insert(spec.name.clone(), h2 as f32);
+            }
+        }
+    }
+
+    out
+}
+
+pub fn compute(
+    alive: &[u8],
+    tribe_id: &[u16],
+    genes: &Genes,
+    registry: &GeneRegistry,
+    tracker: &SelectionTracker,
+    count: usize,
+) -> EvolutionReport {
+    let mut members: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+    let mut everyone = Vec::new();
+    for i in 0..count {
+        if alive[i] != 0 {
+            members.entry(tribe_id[i]).or_default().push(i);
+            everyone.push(i);
+        }
+    }
+
+    let by_tribe = members
+        .iter()
+        .map(|(tribe, list)| {
+            let stats = group_stats(list, tracker.by_tribe.get(tribe), genes, registry);
+            (tribe.to_string(), stats)
+        })
+        .collect();
+
+    EvolutionReport {
+        by_tribe,
+        global: group_stats(&everyone, Some(&tracker.global), genes, registry),
+    }
+}
@@ -0,0 +1,226 @@
+// Tabular export of recorded history and entity dumps for offline analysis
+// Both tables come as CSV and, with the "arrow" feature, as Arrow IPC files
+// (pandas: pd.read_csv / pyarrow.ipc.open_file(...).read_all().to_pandas(), R: arrow::read_ipc_file).
+//
+// History columns:  tick, time, population, births, deaths, kills, starved, food_total,
+//                   pop_tribe_<t>..., mean_<gene>...
+// Entity columns:   tick, id, tribe, x, y, energy, age, <gene>...
+
+use std::fmt::Write;
+
+use crate::genes::GeneRegistry;
+use crate::recorder::Sample;
+
+// Full-population snapshots, one row per living entity per dump, stored column-wise
+#[derive(Default)]
+pub struct EntityTable {
+    tick: Vec<u64>,
+    id: Vec<u64>,
+    tribe: Vec<u16>,
+    x: Vec<f32>,
+    y: Vec<f32>,
+    energy: Vec<f32>,
+    age: Vec<f32>,
+    // Row-major, one registry-length genome per row
+    genes: Vec<f32>,
+}
+
+impl EntityTable {
+    #[allow(clippy::too_many_arguments)]
+    pub fn push(
+        &mut self,
+        tick: u64,
+        id: u64,
+        tribe: u16,
+        x: f32,
+        y: f32,
+        energy: f32,
+        age: f32,
+        genes: &[f32],
+    ) {
+        self.tick.push(tick);
+        self.id.push(id);
+        self.tribe.push(tribe);
+        self.x.push(x);
+        self.y.push(y);
+        self.energy.push(energy);
+        self.age.push(age);
+        self.genes.extend_from_slice(genes);
+    }
+
+    pub fn len(&self) -> usize {
+        self.tick.len()
+    }
+
+    pub fn last_tick(&self) -> Option<u64> {
+        self.tick.last().copied()
+    }
+
+    pub fn clear(&mut self) {
+        *self = EntityTable::default();
+    }
+}
+
+// Gene names are user-defined, so quote them when they would break the row
+pub fn csv_field(name: &str) -> String {
+    if name.contains([',', '"', '\n']) {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    } else {
+        name.to_string()
+    }
+}
+
+fn tribe_count(samples: &[Sample]) -> usize {
+    samples.iter().map(|s| s.population.len()).max().unwrap_or(0)
+}
+
+pub fn history_csv(samples: &[Sample], registry: &GeneRegistry) -> String {
+    let tribes = tribe_count(samples);
+    let mut out = String::from("tick,time,population,births,deaths,kills,starved,food_total");
+    for t in 0..tribes {
+        write!(out, ",pop_tribe_{}", t).unwrap();
+    }
+    for spec in registry.specs() {
+        write!(out, ",{}", csv_field(&format!("mean_{}", spec.name))).unwrap();
+    }
+    out.push('\n');
+
+    for s in samples {
+        write!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            s.tick,
+            s.time,
+            s.population.iter().sum::<u32>(),
+            s.births,
+            s.deaths,
+            s.kills,
+            s.starved,
+            s.food_total,
+        )
+        .unwrap();
+        for t in 0..tribes {
+            write!(out, ",{}", s.population.get(t).copied().unwrap_or(0)).unwrap();
+        }
+        for g in 0..registry.len() {
+            write!(out, ",{}", s.gene_means.get(g).copied().unwrap_or(0.0)).unwrap();
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+pub fn entities_csv(table: &EntityTable, registry: &GeneRegistry) -> String {
+    let stride = registry.len();
+    let mut out = String::from("tick,id,tribe,x,y,energy,age");
+    for spec in registry.specs() {
+        write!(out, ",{}", csv_field(&spec.name)).unwrap();
+    }
+    out.push('\n');
+
+    for (row, genes) in table.genes.chunks_exact(stride).enumerate() {
+        write!(
+            out,
+            "{},{},{},{},{},{},{}",
+            table.tick[row],
+            table.id[row],
+            table.tribe[row],
+            table.x[row],
+            table.y[row],
+            table.energy[row],
+            table.age[row],
+        )
+        .unwrap();
+        for value in genes {
+            write!(out, ",{}", value).unwrap();
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(feature = "arrow")]
+pub use self::ipc::{entities_arrow, history_arrow};
+
+#[cfg(feature = "arrow")]
+mod ipc {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        ArrayRef, Float32Array, Float64Array, RecordBatch, UInt16Array, UInt32Array, UInt64Array,
+    };
+    use arrow_ipc::writer::FileWriter;
+
+    use super::{tribe_count, EntityTable};
+    use crate::genes::GeneRegistry;
+    use crate::recorder::Sample;
+
+    // One record batch in an Arrow IPC file
+    fn write_file(columns: Vec<(String, ArrayRef)>) -> Result<Vec<u8>, String> {
+        let batch = RecordBatch::try_from_iter(columns).map_err(|e| e.to_string())?;
+        let mut buffer = Vec::new();
+        let mut writer =
+            FileWriter::try_new(&mut buffer, &batch.schema()).map_err(|e| e.to_string())?;
+        writer.write(&batch).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        drop(writer);
+        Ok(buffer)
+    }
+
+    pub fn history_arrow(samples: &[Sample], registry: &GeneRegistry) -> Result<Vec<u8>, String> {
+        let u32_column = |f: &dyn Fn(&Sample) -> u32| -> ArrayRef {
+            Arc::new(UInt32Array::from_iter_values(samples.iter().map(f)))
+        };
+
+        let mut columns: Vec<(String, ArrayRef)> = vec![
+            ("tick".into(), Arc::new(UInt64Array::from_iter_values(samples.iter().map(|s| s.tick)))),
+            ("time".into(), Arc::new(Float64Array::from_iter_values(samples.iter().map(|s| s.time)))),
+            ("population".into(), u32_column(&|s| s.population.iter().sum())),
+            ("births".into(), u32_column(&|s| s.births)),
+            ("deaths".into(), u32_column(&|s| s.deaths)),
+            ("kills".into(), u32_column(&|s| s.kills)),
+            ("starved".into(), u32_column(&|s| s.starved)),
+            (
+                "food_total".into(),
+                Arc::new(Float32Array::from_iter_values(samples.iter().map(|s| s.food_total))),
+            ),
+        ];
+        for t in 0..tribe_count(samples) {
+            columns.push((
+                format!("pop_tribe_{}", t),
+                u32_column(&|s| s.population.get(t).copied().unwrap_or(0)),
+            ));
+        }
+        for (g, spec) in registry.specs().iter().enumerate() {
+            let means = samples.iter().map(|s| s.gene_means.get(g).copied().unwrap_or(0.0));
+            columns.push((format!("mean_{}", spec.name), Arc::new(Float32Array::from_iter_values(means))));
+        }
+
+        write_file(columns)
+    }
+
+    pub fn entities_arrow(table: &EntityTable, registry: &GeneRegistry) -> Result<Vec<u8>, String> {
+        let f32_column = |values: &[f32]| -> ArrayRef {
+            Arc::new(Float32Array::from_iter_values(values.iter().copied()))
+        };
+
+        let mut columns: Vec<(String, ArrayRef)> = vec![
+            ("tick".into(), Arc::new(UInt64Array::from_iter_values(table.tick.iter().copied()))),
+            ("id".into(), Arc::new(UInt64Array::from_iter_values(table.id.iter().copied()))),
+            ("tribe".into(), Arc::new(UInt16Array::from_iter_values(table.tribe.iter().copied()))),
+            ("x".into(), f32_column(&table.x)),
+            ("y".into(), f32_column(&table.y)),
+            ("energy".into(), f32_column(&table.energy)),
+            ("age".into(), f32_column(&table.age)),
+        ];
+        let stride = registry.len();
+        for (g, spec) in registry.specs().iter().enumerate() {
+            let values = table.genes.iter().skip(g).step_by(stride).copied();
+            columns.push((spec.name.clone(), Arc::new(Float32Array::from_iter_values(values))));
+        }
+
+        write_file(columns)
+    }
+}
@@ -0,0 +1,183 @@
+// Food grid mirror used for foraging decisions
+// Layout matches the JS FoodSystem buffer (row-major, Y flipped like the biome grid).
+// Regrowth and grazing are only used when the core runs without the JS FoodSystem
+// (native runs); a grid loaded from JS has no regrowth.
+
+// Minimum food in a cell before an entity will graze it (JS consumeAt calibration:
+// 0.1 when desperate up to 3.1 for the pickiest)
+#[inline]
+pub fn grazing_threshold(food_standards: f32) -> f32 {
+    0.1 + food_standards * food_standards * 3.0
+}
+
+pub struct FoodGrid {
+    cells: Vec<f32>,
+    cols: usize,
+    rows: usize,
+    cell_width: f32,
+    cell_height: f32,
+    world_height: f32,
+    // Per-cell maximum and fraction of it regrown per second
+    capacity: f32,
+    regen: f32,
+}
+
+impl FoodGrid {
+    pub fn new(cols: usize, rows: usize, world_width: f32, world_height: f32) -> Self {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+
+        FoodGrid {
+            cells: vec![0.0; cols * rows],
+            cols,
+            rows,
+            cell_width: world_width / cols as f32,
+            cell_height: world_height / rows as f32,
+            world_height,
+            capacity: 0.0,
+            regen: 0.0,
+        }
+    }
+
+    // Fill every cell to `capacity` and regrow `regen` × capacity per second afterwards
+    pub fn fill(&mut self, capacity: f32, regen: f32) {
+        self.capacity = capacity.max(0.0);
+        self.regen = regen.max(0.0);
+        self.cells.fill(self.capacity);
+    }
+
+    // Food the grid holds when every cell is full
+    pub fn total_capacity(&self) -> f32 {
+        self.capacity * self.cells.len() as f32
+    }
+
+    pub fn regrow(&mut self, dt: f32) {
+        if self.regen <= 0.0 {
+            return;
+        }
+        let growth = self.regen * self.capacity * dt;
+        for cell in &mut self.cells {
+            *cell = (*cell + growth).min(self.capacity);
+        }
+    }
+
+    // Eat the cell under (x, y) if it holds more than min_food (same rule as the JS
+    // consumeAt: the cell is emptied and one unit is eaten). Returns the amount eaten.
+    pub fn consume_at(&mut self, x: f32, y: f32, min_food: f32) -> f32 {
+        let (cx, cy) = self.cell_of(x, y);
+        let cell = &mut self.cells[cy * self.cols + cx];
+        if *cell > min_food {
+            *cell = 0.0;
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Copy food values from JS (ignored if the size doesn't match)
+    pub fn load(&mut self, data: &[f32]) {
+        if data.len() == self.cells.len() {
+            self.cells.copy_from_slice(data);
+        }
+    }
+
+    #[inline]
+    pub fn cell_of(&self, x: f32, y: f32) -> (usize, usize) {
+        let flipped_y = self.world_height - y;
+        let cx = ((x / self.cell_width).max(0.0) as usize).min(self.cols - 1);
+        let cy = ((flipped_y / self.cell_height).max(0.0) as usize).min(self.rows - 1);
+        (cx, cy)
+    }
+
+    #[inline]
+    pub fn cell_center(&self, cx: usize, cy: usize) -> (f32, f32) {
+        let x = (cx as f32 + 0.5) * self.cell_width;
+        let y = self.world_height - (cy as f32 + 0.5) * self.cell_height;
+        (x, y)
+    }
+
+    #[inline]
+    pub fn at(&self, cx: usize, cy: usize) -> f32 {
+        self.cells[cy * self.cols + cx]
+    }
+
+    #[inline]
+    pub fn at_world(&self, x: f32, y: f32) -> f32 {
+        let (cx, cy) = self.cell_of(x, y);
+        self.at(cx, cy)
+    }
+
+    // Sum of food over all cells
+    pub fn total(&self) -> f32 {
+        self.cells.iter().sum()
+    }
+
+    #[inline]
+    pub fn min_cell_extent(&self) -> f32 {
+        self.cell_width.min(self.cell_height)
+    }
+
+    // Richest cell above min_food within radius_cells and vision, respecting the view cone
+    // (cells within half vision are always sensed). Returns the cell center and its food.
+    #[allow(clippy::too_many_arguments)]
+    pub fn best_patch(
+        &self,
+        x: f32,
+        y: f32,
+        radius_cells: i32,
+        vision: f32,
+        view_dir: (f32, f32),
+        view_cos_threshold: f32,
+        min_food: f32,
+    ) -> Option<(f32, f32, f32)> {
+        let (fx, fy) = self.cell_of(x, y);
+        let vision_sq = vision * vision;
+        let mut best: Option<(f32, f32, f32)> = None;
+        let mut best_food = min_food;
+
+        for dy in -radius_cells..=radius_cells {
+            for dx in -radius_cells..=radius_cells {
+                let nx = fx as i32 + dx;
+                let ny = fy as i32 + dy;
+                if nx < 0 || ny < 0 || nx >= self.cols as i32 || ny >= self.rows as i32 {
+                    continue;
+                }
+
+                let food = self.at(nx as usize, ny as usize);
+                if food <= best_food {
+                    continue;
+                }
+
+                let (cx, cy) = self.cell_center(nx as usize, ny as usize);
+                let to_x = cx - x;
+                let to_y = cy - y;
+                let dist_sq = to_x * to_x + to_y * to_y;
+                if dist_sq > vision_sq {
+                    continue;
+                }
+
+                if dist_sq > vision_sq * 0.25 {
+                    let dot = (to_x * view_dir.0 + to_y * view_dir.1) / dist_sq.sqrt();
+                    if dot <= view_cos_threshold {
+                        continue;
+                    }
+                }
+
+                best_food = food;
+                best = Some((cx, cy, food));
+            }
+        }
+
+        best
+    }
+}
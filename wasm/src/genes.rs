@@ -0,0 +1,342 @@
+// Gene registry and per-entity gene buffer
+// The registry describes every gene (name, range, mutation scale, default) and fixes the
+// layout of the flat gene buffer shared with JS. The nine core genes the behavior model
+// reads may appear in any order; extra genes (colorHue, size, lifespan, ...) are carried,
+// clamped and mutated without any other module knowing about them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Rng;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneSpec {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    // Standard deviation of a mutation step, in gene units
+    pub mutation_scale: f32,
+    pub default: f32,
+}
+
+impl GeneSpec {
+    // Width of the valid range, used to normalize genetic distances
+    #[inline]
+    pub fn span(&self) -> f32 {
+        (self.max - self.min).max(f32::EPSILON)
+    }
+
+    pub fn new(name: &str, min: f32, max: f32, mutation_scale: f32, default: f32) -> Self {
+        GeneSpec {
+            name: name.to_string(),
+            min,
+            max,
+            mutation_scale,
+            default,
+        }
+    }
+}
+
+// Genes the behavior model depends on; names match GeneSpec in src/sim/types.ts
+pub const CORE_GENES: [&str; 9] = [
+    "speed",
+    "vision",
+    "metabolism",
+    "reproChance",
+    "aggression",
+    "cohesion",
+    "foodStandards",
+    "diet",
+    "viewAngle",
+];
+
+// Body size multiplier; scales upkeep, speed, combat, prey value and personal space
+pub const SIZE_GENE: &str = "size";
+// Separation radius of a size 1.0 entity
+pub const BASE_RADIUS: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crossover {
+    Uniform,
+    Blend,
+}
+
+// Buffer index of each core gene the Rust model reads
+#[derive(Debug, Clone, Copy)]
+pub struct CoreLayout {
+    pub speed: usize,
+    pub vision: usize,
+    pub metabolism: usize,
+    pub aggression: usize,
+    pub cohesion: usize,
+    pub food_standards: usize,
+    pub diet: usize,
+    pub repro_chance: usize,
+    pub view_angle: usize,
+    // Optional body size gene (entities are size 1.0 without it)
+    pub size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneRegistry {
+    genes: Vec<GeneSpec>,
+}
+
+impl Default for GeneRegistry {
+    // The original nine-gene layout (ranges and defaults from src/sim/genes.ts)
+    fn default() -> Self {
+        GeneRegistry {
+            genes: vec![
+                GeneSpec::new("speed", 5.0, 25.0, 1.8, 15.0),
+                GeneSpec::new("vision", 5.0, 100.0, 2.4, 20.0),
+                GeneSpec::new("metabolism", 0.01, 2.0, 0.02, 0.15),
+                GeneSpec::new("reproChance", 0.0, 0.2, 0.0018, 0.012),
+                GeneSpec::new("aggression", 0.0, 1.0, 0.144, 0.3),
+                GeneSpec::new("cohesion", 0.0, 0.75, 0.144, 0.5),
+                GeneSpec::new("foodStandards", 0.0, 1.0, 0.18, 0.3),
+                GeneSpec::new("diet", -1.0, 1.0, 0.24, -0.5),
+                GeneSpec::new("viewAngle", 30.0, 180.0, 2.4, 120.0),
+                GeneSpec::new(SIZE_GENE, 0.5, 2.0, 0.06, 1.0),
+            ],
+        }
+    }
+}
+
+impl GeneRegistry {
+    // Registry from an explicit gene list; every core gene must be present exactly once
+    pub fn new(genes: Vec<GeneSpec>) -> Result<Self, String> {
+        for (i, gene) in genes.iter().enumerate() {
+            if genes[..i].iter().any(|g| g.name == gene.name) {
+                return Err(format!("duplicate gene '{}'", gene.name));
+            }
+            if gene.min > gene.max {
+                return Err(format!("gene '{}' has min > max", gene.name));
+            }
+        }
+
+        let registry = GeneRegistry { genes };
+        if let Some(missing) = CORE_GENES.iter().find(|name| registry.index_of(name).is_none()) {
+            return Err(format!("missing core gene '{}'", missing));
+        }
+        Ok(registry)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.genes.len()
+    }
+
+    pub fn specs(&self) -> &[GeneSpec] {
+        &self.genes
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.genes.iter().position(|g| g.name == name)
+    }
+
+    // Root-mean-square gene difference with each gene normalized by its range (0 = identical,
+    // 1 = every gene at opposite ends of its range)
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        let sum: f32 = self
+            .genes
+            .iter()
+            .zip(a.iter().zip(b))
+            .map(|(spec, (x, y))| {
+                let d = (x - y) / spec.span();
+                d * d
+            })
+            .sum();
+        (sum / self.genes.len().max(1) as f32).sqrt()
+    }
+
+    fn layout(&self) -> CoreLayout {
+        let idx = |name: &str| self.index_of(name).expect("core genes validated on construction");
+        CoreLayout {
+            speed: idx("speed"),
+            vision: idx("vision"),
+            metabolism: idx("metabolism"),
+            aggression: idx("aggression"),
+            cohesion: idx("cohesion"),
+            food_standards: idx("foodStandards"),
+            diet: idx("diet"),
+            repro_chance: idx("reproChance"),
+            view_angle: idx("viewAngle"),
+            size: self.index_of(SIZE_GENE),
+        }
+    }
+}
+
+// Flat gene buffer (stride = registry length) with named access to the core genes
+pub struct Genes {
+    data: Vec<f32>,
+    stride: usize,
+    layout: CoreLayout,
+}
+
+impl Genes {
+    pub fn new(registry: &GeneRegistry, capacity: usize) -> Self {
+        let mut data = Vec::with_capacity(capacity * registry.len());
+        for _ in 0..capacity {
+            data.extend(registry.specs().iter().map(|g| g.default));
+        }
+
+        Genes {
+            data,
+            stride: registry.len(),
+            layout: registry.layout(),
+        }
+    }
+
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    #[inline]
+    pub fn get(&self, entity: usize, gene: usize) -> f32 {
+        self.data[entity * self.stride + gene]
+    }
+
+    #[inline]
+    pub fn entity(&self, entity: usize) -> &[f32] {
+        &self.data[entity * self.stride..(entity + 1) * self.stride]
+    }
+
+    #[inline]
+    pub fn set(&mut self, entity: usize, gene: usize, value: f32) {
+        self.data[entity * self.stride + gene] = value;
+    }
+
+    #[inline]
+    fn entity_mut(&mut self, entity: usize) -> &mut [f32] {
+        &mut self.data[entity * self.stride..(entity + 1) * self.stride]
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.data
+    }
+
+    // Copy `count` entities from a buffer laid out with `source_stride` genes per entity
+    pub fn load(&mut self, source: &[f32], source_stride: usize, count: usize) {
+        if source_stride == self.stride {
+            let n = (count * self.stride).min(source.len()).min(self.data.len());
+            self.data[..n].copy_from_slice(&source[..n]);
+            return;
+        }
+
+        let width = source_stride.min(self.stride);
+        for (i, chunk) in source.chunks_exact(source_stride).take(count).enumerate() {
+            if (i + 1) * self.stride > self.data.len() {
+                break;
+            }
+            self.entity_mut(i)[..width].copy_from_slice(&chunk[..width]);
+        }
+    }
+
+    #[inline]
+    pub fn speed(&self, i: usize) -> f32 {
+        self.get(i, self.layout.speed)
+    }
+
+    #[inline]
+    pub fn vision(&self, i: usize) -> f32 {
+        self.get(i, self.layout.vision)
+    }
+
+    #[inline]
+    pub fn metabolism(&self, i: usize) -> f32 {
+        self.get(i, self.layout.metabolism)
+    }
+
+    #[inline]
+    pub fn aggression(&self, i: usize) -> f32 {
+        self.get(i, self.layout.aggression)
+    }
+
+    // Reproduction probability per second above the energy threshold
+    #[inline]
+    pub fn repro_chance(&self, i: usize) -> f32 {
+        self.get(i, self.layout.repro_chance)
+    }
+
+    #[inline]
+    pub fn cohesion(&self, i: usize) -> f32 {
+        self.get(i, self.layout.cohesion)
+    }
+
+    #[inline]
+    pub fn food_standards(&self, i: usize) -> f32 {
+        self.get(i, self.layout.food_standards)
+    }
+
+    #[inline]
+    pub fn diet(&self, i: usize) -> f32 {
+        self.get(i, self.layout.diet)
+    }
+
+    // View cone in degrees
+    #[inline]
+    pub fn view_angle(&self, i: usize) -> f32 {
+        self.get(i, self.layout.view_angle)
+    }
+
+    #[inline]
+    pub fn size(&self, i: usize) -> f32 {
+        self.layout.size.map_or(1.0, |idx| self.get(i, idx))
+    }
+
+    // Personal-space radius, grows with body size
+    #[inline]
+    pub fn radius(&self, i: usize) -> f32 {
+        BASE_RADIUS * self.size(i)
+    }
+
+    // Top speed: metabolism below 0.15 limits speed, larger bodies are slower (size^-1/4)
+    #[inline]
+    pub fn max_speed(&self, i: usize) -> f32 {
+        let metabolism_efficiency = (self.metabolism(i) / 0.15).min(1.0);
+        self.speed(i) * metabolism_efficiency / self.size(i).powf(0.25)
+    }
+
+    // Child genome from two parents: each gene either picked from one parent (uniform) or
+    // drawn between them (blend, BLX-alpha style with a 25% extension on both sides)
+    pub fn crossover(
+        &mut self,
+        registry: &GeneRegistry,
+        child: usize,
+        parent_a: usize,
+        parent_b: usize,
+        mode: Crossover,
+        rng: &mut Rng,
+    ) {
+        let a = self.entity(parent_a).to_vec();
+        let b = self.entity(parent_b).to_vec();
+
+        for (g, (value, spec)) in self.entity_mut(child).iter_mut().zip(registry.specs()).enumerate() {
+            *value = match mode {
+                Crossover::Uniform => {
+                    if rng.next_f32() < 0.5 {
+                        a[g]
+                    } else {
+                        b[g]
+                    }
+                }
+                Crossover::Blend => {
+                    let lo = a[g].min(b[g]);
+                    let hi = a[g].max(b[g]);
+                    let extend = (hi - lo) * 0.25;
+                    lo - extend + rng.next_f32() * (hi - lo + extend * 2.0)
+                }
+            }
+            .clamp(spec.min, spec.max);
+        }
+    }
+
+    // Add N(0, mutation_scale × intensity) to every gene of `entity` and clamp to range
+    pub fn mutate(&mut self, registry: &GeneRegistry, entity: usize, intensity: f32, rng: &mut Rng) {
+        for (value, spec) in self.entity_mut(entity).iter_mut().zip(registry.specs()) {
+            *value = (*value + rng.next_gaussian() * spec.mutation_scale * intensity)
+                .clamp(spec.min, spec.max);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// Single-entity inspection for the inspector panel and the debug overlay
+// SimCore::inspect gathers everything the core knows about one slot into an Inspection;
+// follow mode keeps a rolling trail of one entity's position, state and targets for the last
+// N ticks.
+//
+// Entity ids are u64 and reach JS as BigInt (see SimCore::inspect), matching get_entity_id and
+// the event stream; slot indices, cells and ticks are u32 so they stay plain numbers.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::lineage::{Lineage, NO_PARENT};
+use crate::types::BehaviorState;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inspection {
+    pub index: u32,
+    pub id: u64,
+    pub alive: bool,
+    pub tribe: u16,
+    // None until the entity has been clustered
+    pub species: Option<u32>,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub speed: f32,
+    pub energy: f32,
+    pub age: f32,
+    pub panic: f32,
+    pub crowd_stress: f32,
+    pub pack_bonus: f32,
+    pub enemy_strength: f32,
+    // Steered by the tribe's brain instead of the steering pipeline
+    pub neural: bool,
+    pub genes: BTreeMap<String, f32>,
+    pub behavior: BehaviorInfo,
+    pub targets: Targets,
+    pub neighbors: Neighbors,
+    // None for slots the lineage log doesn't know (e.g. spawned from JS without register_birth)
+    pub lineage: Option<LineageInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BehaviorInfo {
+    pub state: BehaviorState,
+    // Seconds in the current state
+    pub timer: f32,
+    pub scarcity: f32,
+    pub heading: [f32; 2],
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityTarget {
+    pub index: u32,
+    pub id: u64,
+    pub tribe: u16,
+    pub position: [f32; 2],
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoodTarget {
+    pub col: u32,
+    pub row: u32,
+    pub position: [f32; 2],
+    pub food: f32,
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Targets {
+    pub prey: Option<EntityTarget>,
+    pub rival: Option<EntityTarget>,
+    // Partner sexual reproduction would pick right now
+    pub mate: Option<EntityTarget>,
+    pub food: Option<FoodTarget>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbors {
+    // As counted by the last movement pass (line of sight and view cone applied)
+    pub allies: u16,
+    pub enemies: u16,
+    // Everyone within vision radius now, regardless of sight lines
+    pub in_vision: u32,
+    pub same_tribe: u32,
+    pub same_species: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineageInfo {
+    // Known parents (none for founders)
+    pub parents: Vec<u64>,
+    pub birth_tick: u32,
+    // Longest chain of recorded ancestors (founders are generation 0)
+    pub generation: u32,
+    pub living_children: u32,
+}
+
+impl EntityTarget {
+    pub fn new(index: usize, id: u64, tribe: u16, position: [f32; 2], from: [f32; 2]) -> Self {
+        EntityTarget { index: index as u32, id, tribe, position, distance: distance(from, position) }
+    }
+}
+
+pub fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt()
+}
+
+// Generation of `id` from the lineage log, memoized over the ancestors it visits
+pub fn generation(lineage: &Lineage, id: u64) -> u32 {
+    let mut memo: HashMap<u64, u32> = HashMap::new();
+    let mut stack = vec![id];
+
+    while let Some(&top) = stack.last() {
+        let parents = lineage.get(top).map_or([NO_PARENT; 2], |r| r.parents);
+        let pending: Vec<u64> = parents
+            .into_iter()
+            .filter(|&p| p != NO_PARENT && lineage.get(p).is_some() && !memo.contains_key(&p))
+            .collect();
+        if pending.is_empty() {
+            let depth = parents
+                .iter()
+                .filter_map(|p| memo.get(p))
+                .map(|g| g + 1)
+                .max()
+                .unwrap_or(0);
+            memo.insert(top, depth);
+            stack.pop();
+        } else {
+            stack.extend(pending);
+        }
+    }
+
+    memo[&id]
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowFrame {
+    pub tick: u32,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub energy: f32,
+    pub state: BehaviorState,
+    // Where the entity's attention was: prey, territorial rival and food patch positions
+    pub prey: Option<[f32; 2]>,
+    pub rival: Option<[f32; 2]>,
+    pub food: Option<[f32; 2]>,
+}
+
+// Rolling trail of one entity, keyed by id so a recycled slot isn't mistaken for it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Follower {
+    pub id: u64,
+    pub index: u32,
+    // Cleared once the entity dies; the trail up to its death is kept
+    pub alive: bool,
+    #[serde(skip)]
+    max_frames: usize,
+    pub frames: VecDeque<FollowFrame>,
+}
+
+impl Follower {
+    pub fn new(index: usize, id: u64, ticks: usize) -> Self {
+        let max_frames = ticks.max(1);
+        Follower {
+            id,
+            index: index as u32,
+            alive: true,
+            max_frames,
+            frames: VecDeque::with_capacity(max_frames),
+        }
+    }
+
+    pub fn push(&mut self, frame: FollowFrame) {
+        if self.frames.len() == self.max_frames {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+}
@@ -3,18 +3,78 @@ mod movement;
 mod physics;
 mod types;
 mod collision;
+mod pathfinding;
+mod food;
+mod steering;
+mod energy;
+mod behavior;
+mod brain;
+mod genes;
+mod reproduction;
+mod species;
+mod lineage;
+mod stats;
+mod evolution;
+mod recorder;
+pub mod analysis;
+mod export;
+mod rng;
+mod combat;
+mod events;
+mod inspect;
+pub mod scenario;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod sweep;
 
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use web_sys::console;
 
+use types::{BehaviorState, DeathCause};
+
 // Re-export collision detection
 pub use collision::BiomeCollisionMap;
 
-// Performance logging macro
+const DEFAULT_SEED: u32 = 0x2545_f491;
+// Species clustering: max normalized genetic distance to a representative, and ticks between updates
+const DEFAULT_SPECIES_THRESHOLD: f32 = 0.15;
+const DEFAULT_SPECIES_INTERVAL: u64 = 60;
+const DEFAULT_LINEAGE_PRUNE_INTERVAL: u64 = 600;
+// Offspring brains: share of weights perturbed, and the perturbation size
+const BRAIN_MUTATION_RATE: f32 = 0.1;
+const BRAIN_MUTATION_SCALE: f32 = 0.2;
+
+// Performance logging macro (silent in native builds)
+#[cfg(target_arch = "wasm32")]
 macro_rules! log {
     ($($t:tt)*) => (console::log_1(&format!($($t)*).into()))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+macro_rules! log {
+    ($($t:tt)*) => {{
+        let _ = format_args!($($t)*);
+    }};
+}
+
+// Milliseconds from a monotonic clock, for batch timings
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
 // Main simulation core exposed to JavaScript
 #[wasm_bindgen]
 pub struct SimCore {
@@ -25,15 +85,59 @@ pub struct SimCore {
     vel_y: Vec<f32>,
     energy: Vec<f32>,
     age: Vec<f32>,
+    // Alarm level (0-1) from nearby predators, shared with herd mates
+    panic: Vec<f32>,
+    // min(1, neighbors in vision / 15), refreshed by the movement pass
+    crowd_stress: Vec<f32>,
+    // Current prey (-1 if none), shared within hunting packs
+    hunt_target: Vec<i32>,
+    // Pack hunting bonus (0-1) applied to combat damage and success
+    pack_bonus: Vec<f32>,
+    // Neighbor counts, summed enemy strength and nearest rival (-1 if none) from the last scan
+    nearby_allies: Vec<u16>,
+    nearby_enemies: Vec<u16>,
+    enemy_strength: Vec<f32>,
+    rival: Vec<i32>,
+    // Behavioral state, dissatisfaction and migration heading
+    behavior: behavior::BehaviorStates,
+    // Neural-network weights (own buffer, see brain.rs) and which tribes they steer
+    brains: brain::Brains,
     alive: Vec<u8>,
     tribe_id: Vec<u16>,
     
-    // Genes (9 components per entity)
-    genes: Vec<f32>,
+    // Gene layout and the flat gene buffer it describes: registry.len() genes per entity,
+    // in registry order (see genes.rs)
+    gene_registry: genes::GeneRegistry,
+    genes: genes::Genes,
     
     // Spatial acceleration structure
     spatial_hash: spatial_hash::SpatialHash,
     
+    // Terrain (optional until JS provides the biome map)
+    collision_map: Option<BiomeCollisionMap>,
+    pathfinder: Option<pathfinding::Pathfinder>,
+    // Max terrain raycasts per entity per movement step (0 disables occlusion)
+    los_budget: usize,
+    
+    // Food grid mirror for foraging (optional until JS provides it)
+    food_grid: Option<food::FoodGrid>,
+    
+    // How much alarm carries from panicking allies (0 disables herd panic)
+    herd_panic: f32,
+    
+    // Weighted steering behaviors (flocking, hunting, foraging, ...)
+    steering: steering::SteeringPipeline,
+    
+    // Upkeep coefficients and scratch list of deaths from the last energy pass
+    energy_config: energy::EnergyConfig,
+    reproduction_config: reproduction::ReproductionConfig,
+    // Entities that already bred this tick (cleared by finish_tick)
+    mated: Vec<bool>,
+    deaths: Vec<(usize, DeathCause)>,
+    
+    // Deterministic randomness for combat and reproduction
+    rng: rng::Rng,
+    
     // World parameters
     world_width: f32,
     world_height: f32,
@@ -41,29 +145,95 @@ pub struct SimCore {
     // Capacity
     capacity: usize,
     count: usize,
+    
+    // Completed simulation ticks and elapsed sim seconds (advanced by finish_tick)
+    tick: u64,
+    time: f64,
+    // Cumulative births/deaths/kills per tribe
+    counters: stats::Counters,
+    // Names and colours for get_stats, indexed by tribe id
+    tribe_info: Vec<stats::TribeInfo>,
+    // Parent and offspring genes of births since the last counter reset
+    selection: evolution::SelectionTracker,
+    // History sampled every recorder interval, and the counter totals at the last sample
+    recorder: recorder::Recorder,
+    recorded_totals: stats::TribeCounters,
+    // Genetic clustering, refreshed every species_interval ticks (0 = only on demand)
+    species: species::SpeciesTracker,
+    species_interval: u64,
+    
+    // Stable id per slot (0 = empty) and the append-only ancestry log
+    entity_id: Vec<u64>,
+    lineage: lineage::Lineage,
+    // Ticks between automatic pruning of extinct branches (0 = only on demand)
+    lineage_prune_interval: u64,
+    
+    // Full-population snapshots for export, taken every entity_dump_interval ticks (0 = off)
+    entity_dumps: export::EntityTable,
+    entity_dump_interval: u64,
+    
+    // Births, deaths, kills, biome changes and new species for JS to drain (off by default);
+    // last_biome is each slot's biome at the previous tick (NO_BIOME = not yet seen)
+    events: events::EventQueue,
+    last_biome: Vec<u8>,
+    
+    // Entity whose trajectory and decisions are recorded each tick for the debug overlay
+    follow: Option<inspect::Follower>,
 }
 
 #[wasm_bindgen]
 impl SimCore {
     #[wasm_bindgen(constructor)]
     pub fn new(capacity: usize, world_width: f32, world_height: f32, cell_size: f32) -> Self {
-        log!("Initializing SimCore with capacity: {}", capacity);
-        
-        SimCore {
-            pos_x: vec![0.0; capacity],
-            pos_y: vec![0.0; capacity],
-            vel_x: vec![0.0; capacity],
-            vel_y: vec![0.0; capacity],
-            energy: vec![50.0; capacity],
-            age: vec![0.0; capacity],
-            alive: vec![0; capacity],
-            tribe_id: vec![0; capacity],
-            genes: vec![0.0; capacity * 9],
-            spatial_hash: spatial_hash::SpatialHash::new(world_width, world_height, cell_size, capacity),
-            world_width,
-            world_height,
-            capacity,
-            count: 0,
+        Self::build(capacity, world_width, world_height, cell_size, genes::GeneRegistry::default())
+    }
+    
+    // Construct with a custom gene registry: an array of {name, min, max, mutation_scale, default}
+    // that must include the nine core genes (in any order) and may add more
+    pub fn with_genes(
+        capacity: usize,
+        world_width: f32,
+        world_height: f32,
+        cell_size: f32,
+        genes: JsValue,
+    ) -> Result<SimCore, JsValue> {
+        let specs: Vec<genes::GeneSpec> = serde_wasm_bindgen::from_value(genes)?;
+        let registry = genes::GeneRegistry::new(specs).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::build(capacity, world_width, world_height, cell_size, registry))
+    }
+    
+    // Gene layout as [{name, min, max, mutation_scale, default}, ...] in buffer order
+    pub fn get_gene_registry(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(self.gene_registry.specs())?)
+    }
+    
+    // Genes per entity in the gene buffer
+    pub fn gene_count(&self) -> usize {
+        self.genes.stride()
+    }
+    
+    // Buffer index of a gene by name, or -1 if it isn't registered
+    pub fn gene_index(&self, name: &str) -> i32 {
+        self.gene_registry.index_of(name).map_or(-1, |i| i as i32)
+    }
+    
+    pub fn get_genes_ptr(&self) -> *const f32 {
+        self.genes.as_slice().as_ptr()
+    }
+    
+    // Copy of one entity's genes in registry order
+    pub fn get_entity_genes(&self, index: usize) -> Vec<f32> {
+        if index < self.capacity {
+            self.genes.entity(index).to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+    
+    // Mutate every gene of an entity by N(0, mutation_scale × intensity), clamped to its range
+    pub fn mutate_genes(&mut self, index: usize, intensity: f32) {
+        if index < self.capacity {
+            self.genes.mutate(&self.gene_registry, index, intensity, &mut self.rng);
         }
     }
     
@@ -72,6 +242,11 @@ impl SimCore {
         self.count = count.min(self.capacity);
     }
     
+    // Living entities among the first `count` slots
+    pub fn living_count(&self) -> u32 {
+        self.alive[..self.count].iter().filter(|&&a| a != 0).count() as u32
+    }
+    
     // Get pointers for zero-copy SharedArrayBuffer access
     pub fn get_pos_x_ptr(&self) -> *const f32 {
         self.pos_x.as_ptr()
@@ -89,6 +264,23 @@ impl SimCore {
         self.vel_y.as_ptr()
     }
     
+    pub fn get_energy_ptr(&self) -> *const f32 {
+        self.energy.as_ptr()
+    }
+    
+    pub fn get_alive_ptr(&self) -> *const u8 {
+        self.alive.as_ptr()
+    }
+    
+    pub fn get_crowd_stress_ptr(&self) -> *const f32 {
+        self.crowd_stress.as_ptr()
+    }
+    
+    // Reproduction is blocked while an entity is too crowded
+    pub fn can_reproduce_crowd(&self, index: usize) -> bool {
+        index < self.count && self.crowd_stress[index] <= movement::REPRODUCTION_CROWD_LIMIT
+    }
+    
     // Rebuild spatial hash for current entities
     pub fn rebuild_spatial_hash(&mut self) {
         self.spatial_hash.rebuild(&self.pos_x, &self.pos_y, &self.alive, self.count);
@@ -96,14 +288,20 @@ impl SimCore {
     
     // Process movement for a range of entities (can be called in parallel by different workers)
     pub fn process_movement_batch(&mut self, start_idx: usize, end_idx: usize, dt: f32) -> f32 {
-        let start = web_sys::window()
-            .unwrap()
-            .performance()
-            .unwrap()
-            .now();
+        let start = now_ms();
         
         let end = end_idx.min(self.count);
         
+        let env = movement::MovementEnv {
+            spatial_hash: &self.spatial_hash,
+            terrain: self.collision_map.as_ref(),
+            food: self.food_grid.as_ref(),
+            steering: &self.steering,
+            los_budget: self.los_budget,
+            herd_panic: self.herd_panic,
+            brains: &self.brains,
+        };
+        
         for i in start_idx..end {
             if self.alive[i] == 0 {
                 continue;
@@ -111,27 +309,30 @@ impl SimCore {
             
             movement::process_entity_movement(
                 i,
-                &mut self.pos_x,
-                &mut self.pos_y,
+                &self.pos_x,
+                &self.pos_y,
                 &mut self.vel_x,
                 &mut self.vel_y,
                 &self.energy,
+                &self.alive,
+                &mut self.panic,
+                &mut self.crowd_stress,
+                &mut self.hunt_target,
+                &mut self.pack_bonus,
+                &mut self.nearby_allies,
+                &mut self.nearby_enemies,
+                &mut self.enemy_strength,
+                &mut self.rival,
+                &mut self.behavior,
                 &self.tribe_id,
                 &self.genes,
-                &self.spatial_hash,
-                self.world_width,
-                self.world_height,
+                &env,
+                self.pathfinder.as_mut(),
                 dt,
             );
         }
         
-        let elapsed = web_sys::window()
-            .unwrap()
-            .performance()
-            .unwrap()
-            .now() - start;
-        
-        elapsed as f32
+        (now_ms() - start) as f32
     }
     
     // Integrate physics for a range of entities
@@ -151,7 +352,900 @@ impl SimCore {
         );
     }
     
-    // Load data from SharedArrayBuffers (for initialization)
+    // Age entities and apply upkeep (metabolism, speed², diet, age) for a range of entities.
+    // Entities that starve or exceed max age are marked dead; returns the number of deaths.
+    pub fn update_energy_batch(&mut self, start_idx: usize, end_idx: usize, dt: f32) -> u32 {
+        let end = end_idx.min(self.count);
+        
+        self.deaths.clear();
+        energy::update_batch(
+            &mut self.energy,
+            &mut self.age,
+            &self.alive,
+            &self.vel_x,
+            &self.vel_y,
+            &self.genes,
+            start_idx,
+            end,
+            &self.energy_config,
+            dt,
+            &mut self.deaths,
+        );
+        
+        let deaths = std::mem::take(&mut self.deaths);
+        for &(i, cause) in &deaths {
+            self.mark_dead(i, cause);
+        }
+        let count = deaths.len() as u32;
+        self.deaths = deaths;
+        
+        count
+    }
+    
+    // Let hunters in range strike their targets; returns the number of kills
+    pub fn resolve_combat_batch(&mut self, start_idx: usize, end_idx: usize, dt: f32) -> u32 {
+        let end = end_idx.min(self.count);
+        let mut kills = 0;
+        
+        for i in start_idx..end {
+            if self.alive[i] == 0 {
+                continue;
+            }
+            
+            let env = combat::CombatEnv {
+                pos_x: &self.pos_x,
+                pos_y: &self.pos_y,
+                alive: &self.alive,
+                genes: &self.genes,
+                hunt_target: &self.hunt_target,
+                pack_bonus: &self.pack_bonus,
+                crowd_stress: &self.crowd_stress,
+                nearby_allies: &self.nearby_allies,
+                nearby_enemies: &self.nearby_enemies,
+                enemy_strength: &self.enemy_strength,
+                rival: &self.rival,
+            };
+            
+            let energy_before = self.energy[i];
+            
+            // Predators hunt first; everyone else may defend territory against the nearest rival
+            let victim = combat::resolve_predation(i, &env, &mut self.energy, &mut self.rng, dt)
+                .or_else(|| combat::resolve_territorial(i, &env, &mut self.energy, &mut self.rng, dt));
+            
+            if let Some(victim) = victim {
+                self.record_kill(i, victim, (self.energy[i] - energy_before).max(0.0));
+                kills += 1;
+            }
+            
+            // Losing a territorial clash can be fatal for the attacker too; the kill goes to the
+            // rival only if it survived the clash
+            if energy_before > 0.0 && self.energy[i] <= 0.0 {
+                let rival = usize::try_from(self.rival[i])
+                    .ok()
+                    .filter(|&rival| self.alive[rival] != 0 && Some(rival) != victim);
+                match rival {
+                    Some(rival) => self.record_kill(rival, i, 0.0),
+                    None => self.mark_dead(i, DeathCause::Killed),
+                }
+                kills += 1;
+            }
+        }
+        
+        kills
+    }
+    
+    pub fn set_seed(&mut self, seed: u32) {
+        self.rng = rng::Rng::new(seed);
+    }
+    
+    pub fn get_pack_bonus_ptr(&self) -> *const f32 {
+        self.pack_bonus.as_ptr()
+    }
+    
+    // Behavioral state per entity (BehaviorState as u8)
+    pub fn get_behavior_state_ptr(&self) -> *const u8 {
+        self.behavior.state.as_ptr()
+    }
+    
+    // Foraging (the default state) past capacity
+    pub fn get_behavior_state(&self, index: usize) -> BehaviorState {
+        if index >= self.capacity {
+            return BehaviorState::default();
+        }
+        self.behavior.get(index)
+    }
+    
+    // Switch a tribe between the steering pipeline and its evolved neural controller.
+    // Living members that never got a brain are given a random one so they don't freeze.
+    pub fn set_tribe_neural(&mut self, tribe: u16, enabled: bool) {
+        self.brains.set_tribe_controlled(tribe, enabled);
+        if !enabled {
+            return;
+        }
+        for i in 0..self.count {
+            if self.alive[i] != 0 && self.tribe_id[i] == tribe && self.brains.is_blank(i) {
+                self.brains.randomize(i, brain::INITIAL_WEIGHT_SCALE, &mut self.rng);
+            }
+        }
+    }
+    
+    // Network weights per entity in the brain buffer
+    pub fn brain_weight_count(&self) -> usize {
+        brain::BRAIN_WEIGHTS
+    }
+    
+    pub fn get_brain_ptr(&self) -> *const f32 {
+        self.brains.as_slice().as_ptr()
+    }
+    
+    // Import saved weights (brain_weight_count() floats per entity, starting at entity 0)
+    pub fn load_brains(&mut self, weights: &[f32]) {
+        let dst = self.brains.as_mut_slice();
+        let n = weights.len().min(dst.len());
+        dst[..n].copy_from_slice(&weights[..n]);
+    }
+    
+    // Seed an entity with random weights ~ N(0, scale)
+    pub fn randomize_brain(&mut self, index: usize, scale: f32) {
+        if index < self.capacity {
+            self.brains.randomize(index, scale, &mut self.rng);
+        }
+    }
+    
+    // Offspring brain: copy of the parent's with each weight mutated with probability `rate`
+    // (register_birth already does this at the default rate; use this to override it)
+    pub fn inherit_brain(&mut self, parent: usize, child: usize, rate: f32, scale: f32) {
+        if parent < self.capacity && child < self.capacity {
+            self.brains.copy(parent, child);
+            self.brains.mutate(child, rate, scale, &mut self.rng);
+        }
+    }
+    
+    pub fn mutate_brain(&mut self, index: usize, rate: f32, scale: f32) {
+        if index < self.capacity {
+            self.brains.mutate(index, rate, scale, &mut self.rng);
+        }
+    }
+    
+    pub fn get_nearby_allies_ptr(&self) -> *const u16 {
+        self.nearby_allies.as_ptr()
+    }
+    
+    pub fn get_nearby_enemies_ptr(&self) -> *const u16 {
+        self.nearby_enemies.as_ptr()
+    }
+    
+    // Override energy coefficients; missing fields keep their defaults
+    pub fn configure_energy(&mut self, config: JsValue) -> Result<(), JsValue> {
+        self.energy_config = serde_wasm_bindgen::from_value(config)?;
+        Ok(())
+    }
+    
+    pub fn get_energy_config(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.energy_config)?)
+    }
+    
+    // Override reproduction settings (sexual mode, thresholds, crossover, compatibility)
+    pub fn configure_reproduction(&mut self, config: JsValue) -> Result<(), JsValue> {
+        self.reproduction_config = serde_wasm_bindgen::from_value(config)?;
+        Ok(())
+    }
+    
+    pub fn get_reproduction_config(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.reproduction_config)?)
+    }
+    
+    // Normalized genetic distance between two entities (0 = identical)
+    pub fn genetic_distance(&self, a: usize, b: usize) -> f32 {
+        if a >= self.capacity || b >= self.capacity {
+            return 0.0;
+        }
+        self.gene_registry.distance(self.genes.entity(a), self.genes.entity(b))
+    }
+    
+    // Sexual reproduction for a range of entities (no-op unless enabled); children take free
+    // slots anywhere in the buffers. Returns the number of births.
+    pub fn reproduce_batch(&mut self, start_idx: usize, end_idx: usize, dt: f32) -> u32 {
+        if !self.reproduction_config.sexual {
+            return 0;
+        }
+        
+        let end = end_idx.min(self.count);
+        let mut births = 0;
+        let mut free_cursor = 0;
+        
+        for i in start_idx..end {
+            if self.alive[i] == 0
+                || self.mated[i]
+                || self.crowd_stress[i] > movement::REPRODUCTION_CROWD_LIMIT
+            {
+                continue;
+            }
+            
+            let config = &self.reproduction_config;
+            if !reproduction::wants_to_mate(i, &self.energy, &self.genes, config, &mut self.rng, dt) {
+                continue;
+            }
+            
+            let mate = reproduction::find_mate(
+                i,
+                &self.pos_x,
+                &self.pos_y,
+                &self.energy,
+                &self.alive,
+                &self.mated,
+                &self.tribe_id,
+                &self.genes,
+                &self.gene_registry,
+                &self.spatial_hash,
+                config,
+            );
+            let Some(mate) = mate else {
+                continue;
+            };
+            
+            let Some(child) = (free_cursor..self.capacity).find(|&j| self.alive[j] == 0) else {
+                break;
+            };
+            free_cursor = child + 1;
+            
+            self.spawn_offspring(child, i, mate);
+            births += 1;
+        }
+        
+        births
+    }
+    
+    // Close the current tick after all batches ran; runs periodic bookkeeping
+    pub fn finish_tick(&mut self, dt: f32) {
+        self.tick += 1;
+        self.time += dt as f64;
+        
+        // Cleared per tick rather than per batch so a later slice can't reuse a partner
+        self.mated.fill(false);
+        
+        if self.species_interval > 0 && self.tick.is_multiple_of(self.species_interval) {
+            self.update_species();
+        }
+        
+        if self.recorder.due(self.tick) {
+            self.record_sample();
+        }
+        
+        if self.lineage_prune_interval > 0 && self.tick.is_multiple_of(self.lineage_prune_interval) {
+            self.lineage.prune();
+        }
+        
+        if self.entity_dump_interval > 0 && self.tick.is_multiple_of(self.entity_dump_interval) {
+            self.dump_entities();
+        }
+        
+        if self.events.enabled() {
+            self.track_biomes();
+        }
+        
+        self.record_follow_frame();
+    }
+    
+    pub fn get_tick(&self) -> f64 {
+        self.tick as f64
+    }
+    
+    // Re-cluster living entities into species now
+    pub fn update_species(&mut self) {
+        let known = self.species.pending_births().len();
+        self.species.update(&self.genes, &self.gene_registry, &self.alive, self.count, self.tick);
+        
+        for &id in &self.species.pending_births()[known..] {
+            // A species founded this update can already be gone again (dropped as empty)
+            if let Some(s) = self.species.species().iter().find(|s| s.id == id) {
+                let event = events::Event::SpeciesFormed { species: id, members: s.members };
+                self.events.push(self.tick, event);
+            }
+        }
+    }
+    
+    pub fn set_species_threshold(&mut self, threshold: f32) {
+        self.species.set_threshold(threshold);
+    }
+    
+    // Ticks between automatic species updates (0 disables them)
+    pub fn set_species_interval(&mut self, ticks: u32) {
+        self.species_interval = ticks as u64;
+    }
+    
+    // Species id per entity (0 = unassigned)
+    pub fn get_species_ptr(&self) -> *const u32 {
+        self.species.membership().as_ptr()
+    }
+    
+    pub fn get_species_id(&self, index: usize) -> u32 {
+        if index < self.capacity {
+            self.species.species_of(index)
+        } else {
+            species::NO_SPECIES
+        }
+    }
+    
+    // Living species as [{id, members, founded, representative}, ...]
+    pub fn get_species(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(self.species.species())?)
+    }
+    
+    // Species births/extinctions since the last call, as {births: [...], extinctions: [...]}
+    pub fn take_species_events(&mut self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.species.take_events())?)
+    }
+    
+    // Queue births, deaths, kills, biome changes and new species for take_events (off by default)
+    pub fn set_events_enabled(&mut self, enabled: bool) {
+        if enabled && !self.events.enabled() {
+            // Biomes seen while the queue was off are stale
+            self.last_biome.fill(events::NO_BIOME);
+        }
+        self.events.set_enabled(enabled);
+    }
+    
+    pub fn pending_event_count(&self) -> usize {
+        self.events.len()
+    }
+    
+    // Events since the last call as a binary buffer (layout in src/events.rs,
+    // decoder in src/sim/simEvents.ts)
+    pub fn take_events(&mut self) -> Vec<u8> {
+        self.events.drain()
+    }
+    
+    // Name and hue (degrees) get_stats reports for a tribe, mirroring the JS tribeNames/tribeColors
+    pub fn set_tribe_info(&mut self, tribe: u16, name: String, color_hue: f32) {
+        let t = tribe as usize;
+        if self.tribe_info.len() <= t {
+            self.tribe_info.resize(t + 1, stats::TribeInfo::default());
+        }
+        self.tribe_info[t] = stats::TribeInfo { name: Some(name), color_hue };
+    }
+    
+    // Population and gene statistics in the shape of SimulationCore.getStats()
+    pub fn get_stats(&self) -> Result<JsValue, JsValue> {
+        let food = self.food_grid.as_ref().map(|grid| {
+            let capacity = grid.total_capacity();
+            stats::FoodStats {
+                current: grid.total(),
+                capacity,
+                percentage: if capacity > 0.0 { grid.total() / capacity * 100.0 } else { 0.0 },
+            }
+        });
+        let stats = stats::compute(
+            &self.alive,
+            &self.tribe_id,
+            &self.age,
+            &self.energy,
+            &self.genes,
+            &self.gene_registry,
+            &self.counters,
+            &self.tribe_info,
+            food,
+            self.count,
+            self.time,
+        );
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(stats.serialize(&serializer)?)
+    }
+    
+    // Histogram of one gene over its registry range into `out` (length >= bins).
+    // tribe = -1 counts every tribe. Returns the number of entities counted.
+    pub fn gene_histogram(&self, gene: usize, bins: usize, tribe: i32, out: &mut [u32]) -> u32 {
+        stats::gene_histogram(
+            &self.alive,
+            &self.tribe_id,
+            &self.genes,
+            &self.gene_registry,
+            self.count,
+            gene,
+            bins,
+            u16::try_from(tribe).ok(),
+            false,
+            out,
+        )
+    }
+    
+    // One histogram row per tribe id: out[tribe * bins + bin] (tribes beyond `out` are skipped)
+    pub fn gene_histograms_by_tribe(&self, gene: usize, bins: usize, out: &mut [u32]) -> u32 {
+        stats::gene_histogram(
+            &self.alive,
+            &self.tribe_id,
+            &self.genes,
+            &self.gene_registry,
+            self.count,
+            gene,
+            bins,
+            None,
+            true,
+            out,
+        )
+    }
+    
+    // 2D density of two genes (e.g. diet × viewAngle), row-major out[y * bins_x + x].
+    // tribe = -1 counts every tribe.
+    pub fn joint_histogram(
+        &self,
+        gene_x: usize,
+        gene_y: usize,
+        bins_x: usize,
+        bins_y: usize,
+        tribe: i32,
+        out: &mut [u32],
+    ) -> u32 {
+        stats::joint_histogram(
+            &self.alive,
+            &self.tribe_id,
+            &self.genes,
+            &self.gene_registry,
+            self.count,
+            gene_x,
+            gene_y,
+            bins_x,
+            bins_y,
+            u16::try_from(tribe).ok(),
+            out,
+        )
+    }
+    
+    // Recorder settings {interval, capacity, factor, levels}; clears the history
+    pub fn configure_recorder(&mut self, config: JsValue) -> Result<(), JsValue> {
+        let config: recorder::RecorderConfig = serde_wasm_bindgen::from_value(config)?;
+        self.recorder = recorder::Recorder::new(config);
+        Ok(())
+    }
+    
+    pub fn get_recorder_config(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(self.recorder.config())?)
+    }
+    
+    // Recorded samples between two sim times (seconds), at the finest resolution still
+    // covering the range: [{tick, time, population, births, deaths, kills, starved,
+    // foodTotal, geneMeans}, ...]
+    pub fn query_history(&self, from: f64, to: f64) -> Result<JsValue, JsValue> {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(self.recorder.query(from, to).serialize(&serializer)?)
+    }
+    
+    // Full contents of one downsampling level (0 = finest), oldest first
+    pub fn query_history_level(&self, level: usize) -> Result<JsValue, JsValue> {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(self.recorder.level(level).serialize(&serializer)?)
+    }
+    
+    // Predator/prey dynamics over the recorded history between two sim times: prey and
+    // predator counts are summed over the listed tribes. Returns {prey, predator,
+    // lotkaVolterra, predatorLag, events} (see analysis.rs); config may be undefined.
+    pub fn analyze_populations(
+        &self,
+        prey_tribes: &[u16],
+        predator_tribes: &[u16],
+        from: f64,
+        to: f64,
+        config: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let config = if config.is_undefined() || config.is_null() {
+            analysis::AnalysisConfig::default()
+        } else {
+            serde_wasm_bindgen::from_value(config)?
+        };
+        let report = self.population_report(prey_tribes, predator_tribes, from, to, &config);
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(report.serialize(&serializer)?)
+    }
+    
+    pub fn history_len(&self) -> usize {
+        self.recorder.len()
+    }
+    
+    pub fn clear_history(&mut self) {
+        self.recorder.clear();
+    }
+    
+    // Genetic diversity (mean pairwise distance, per-gene entropy) of the living population and
+    // selection differentials / heritability from births since the last counter reset, as
+    // {byTribe: {<tribe>: stats}, global: stats} (see evolution.rs)
+    pub fn get_evolution_stats(&self) -> Result<JsValue, JsValue> {
+        let report = evolution::compute(
+            &self.alive,
+            &self.tribe_id,
+            &self.genes,
+            &self.gene_registry,
+            &self.selection,
+            self.count,
+        );
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(report.serialize(&serializer)?)
+    }
+    
+    // Zero the cumulative births/deaths/kills/starved counters and the selection records
+    pub fn reset_stats_counters(&mut self) {
+        self.counters.reset();
+        self.selection.reset();
+        self.recorded_totals = stats::TribeCounters::default();
+    }
+    
+    // Stable 64-bit id of the entity in a slot (0 if empty)
+    pub fn get_entity_id(&self, index: usize) -> u64 {
+        self.entity_id.get(index).copied().unwrap_or(0)
+    }
+    
+    // Everything known about one slot (SoA fields, named genes, behavior, targets, neighbor
+    // counts, lineage) as an EntityInspection object (see src/sim/types.ts); null past capacity.
+    // Entity ids are BigInt, as from get_entity_id.
+    pub fn inspect(&self, index: usize) -> Result<JsValue, JsValue> {
+        if index >= self.capacity {
+            return Ok(JsValue::NULL);
+        }
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible()
+            .serialize_large_number_types_as_bigints(true);
+        Ok(self.inspection(index).serialize(&serializer)?)
+    }
+    
+    // Record the entity in `index` for the last `ticks` ticks (replaces any previous follow)
+    pub fn follow_entity(&mut self, index: usize, ticks: usize) {
+        self.follow = (index < self.capacity && self.alive[index] != 0)
+            .then(|| inspect::Follower::new(index, self.entity_id[index], ticks));
+    }
+    
+    pub fn stop_following(&mut self) {
+        self.follow = None;
+    }
+    
+    // Followed entity's trail as {id (BigInt), index, alive, frames: [{tick, position, velocity, energy,
+    // state, prey, rival, food}, ...]} oldest first, or null when not following
+    pub fn get_follow_trail(&self) -> Result<JsValue, JsValue> {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible()
+            .serialize_large_number_types_as_bigints(true);
+        Ok(self.follow.serialize(&serializer)?)
+    }
+    
+    // Record a birth made outside the core (e.g. asexual reproduction in the JS worker).
+    // Pass -1 for a missing parent; returns the child's new id. The child inherits a mutated
+    // copy of the first parent's brain, or a fresh random one without parents.
+    pub fn register_birth(&mut self, child: usize, parent: i32, mate: i32) -> u64 {
+        if child >= self.capacity {
+            return 0;
+        }
+        // The slot may still hold an entity JS removed on its own; close out its record
+        let stale = self.entity_id[child];
+        if stale != 0 {
+            self.lineage.record_death(stale, DeathCause::Unknown, self.tick);
+        }
+        let parent_id = |p: i32| {
+            usize::try_from(p)
+                .ok()
+                .and_then(|p| self.entity_id.get(p).copied())
+                .unwrap_or(lineage::NO_PARENT)
+        };
+        let parents = [parent_id(parent), parent_id(mate)];
+        let id = self.lineage.record_birth(parents, self.tribe_id[child], self.tick);
+        self.entity_id[child] = id;
+        self.alive[child] = 1;
+        self.count = self.count.max(child + 1);
+        self.counters.birth(self.tribe_id[child]);
+        self.push_birth(child, parents);
+        self.refresh_selection_baseline();
+        
+        let parent_genes: Vec<&[f32]> = [parent, mate]
+            .into_iter()
+            .filter_map(|p| usize::try_from(p).ok().filter(|&p| p < self.capacity))
+            .map(|p| self.genes.entity(p))
+            .collect();
+        self.selection.record(self.tribe_id[child], &parent_genes, self.genes.entity(child));
+        
+        match usize::try_from(parent).ok().filter(|&p| p < self.capacity) {
+            Some(parent) => {
+                self.brains.copy(parent, child);
+                self.brains.mutate(child, BRAIN_MUTATION_RATE, BRAIN_MUTATION_SCALE, &mut self.rng);
+            }
+            None => self.brains.randomize(child, brain::INITIAL_WEIGHT_SCALE, &mut self.rng),
+        }
+        id
+    }
+    
+    // Record a death that happened outside the core (e.g. in the JS worker): closes the lineage
+    // record at the current tick, counts it and queues a Death event. Ignored for slots with no
+    // recorded entity.
+    pub fn register_death(&mut self, index: usize, cause: DeathCause) {
+        if index < self.capacity && self.entity_id[index] != 0 {
+            self.mark_dead(index, cause);
+        }
+    }
+    
+    // Record a kill made outside the core: credits the killer's tribe, queues a Kill event
+    // (`energy` is what the killer gained) and records the victim's death as Killed
+    pub fn register_kill(&mut self, killer: usize, victim: usize, energy: f32) {
+        if killer < self.capacity && victim < self.capacity && self.entity_id[victim] != 0 {
+            self.record_kill(killer, victim, energy);
+        }
+    }
+    
+    pub fn set_lineage_prune_interval(&mut self, ticks: u32) {
+        self.lineage_prune_interval = ticks as u64;
+    }
+    
+    // Drop dead lineage records with no living descendants; returns how many were removed
+    pub fn prune_lineage(&mut self) -> u32 {
+        self.lineage.prune() as u32
+    }
+    
+    pub fn lineage_len(&self) -> usize {
+        self.lineage.len()
+    }
+    
+    // Evolutionary tree of the recorded lineage (first parent line) in Newick format
+    pub fn export_lineage_newick(&self) -> String {
+        self.lineage.to_newick(self.tick)
+    }
+    
+    // Lineage records as a compact little-endian table (see lineage.rs for the layout)
+    pub fn export_lineage_binary(&self) -> Vec<u8> {
+        self.lineage.to_binary()
+    }
+    
+    // Load the biome traversability grid (1 = traversable) used for pathfinding
+    pub fn load_biome_map(
+        &mut self,
+        traversability: &[u8],
+        grid_width: usize,
+        grid_height: usize,
+        cell_size: f32,
+    ) {
+        let map = BiomeCollisionMap::new(
+            traversability,
+            grid_width,
+            grid_height,
+            cell_size,
+            self.world_width,
+            self.world_height,
+        );
+        self.pathfinder = Some(pathfinding::Pathfinder::new(&map));
+        self.collision_map = Some(map);
+    }
+    
+    // Replace traversability data and rebuild cached flow fields
+    pub fn update_biome_map(&mut self, traversability: &[u8]) {
+        if let (Some(map), Some(pf)) = (self.collision_map.as_mut(), self.pathfinder.as_mut()) {
+            map.update_traversability(traversability);
+            pf.refresh(map);
+        }
+    }
+    
+    // Provide per-cell biome types (0-5) so mountains and forests occlude vision
+    pub fn load_biome_types(&mut self, biome_types: &[u8]) {
+        if let Some(map) = self.collision_map.as_mut() {
+            map.set_biome_types(biome_types);
+        }
+    }
+    
+    pub fn set_biome_opacity(&mut self, biome: u8, opacity: f32) {
+        if let Some(map) = self.collision_map.as_mut() {
+            map.set_biome_opacity(biome, opacity);
+        }
+    }
+    
+    // Terrain raycasts per entity per movement step; once spent, farther neighbors count as
+    // hidden (0 turns occlusion off)
+    pub fn set_los_budget(&mut self, max_raycasts: usize) {
+        self.los_budget = max_raycasts;
+    }
+    
+    // Cap on A* node expansions per query (bounds hunter detour cost)
+    pub fn set_path_budget(&mut self, max_expansions: usize) {
+        if let Some(pf) = self.pathfinder.as_mut() {
+            pf.set_max_expansions(max_expansions);
+        }
+    }
+    
+    // Query an A* path for debugging overlays; returns flattened [x0, y0, x1, y1, ...]
+    pub fn find_path(&mut self, from_x: f32, from_y: f32, to_x: f32, to_y: f32) -> Vec<f32> {
+        let (Some(map), Some(pf)) = (self.collision_map.as_ref(), self.pathfinder.as_mut()) else {
+            return Vec::new();
+        };
+        
+        pf.find_path(map, from_x, from_y, to_x, to_y)
+            .map(|path| path.into_iter().flat_map(|(x, y)| [x, y]).collect())
+            .unwrap_or_default()
+    }
+    
+    // Build (or rebuild) a cached flow field toward goal points given as [x0, y0, x1, y1, ...]
+    pub fn set_flow_field(&mut self, key: u32, goals: &[f32]) {
+        if let (Some(map), Some(pf)) = (self.collision_map.as_ref(), self.pathfinder.as_mut()) {
+            let points: Vec<(f32, f32)> = goals.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+            pf.set_flow_field(map, key, &points);
+        }
+    }
+    
+    pub fn remove_flow_field(&mut self, key: u32) {
+        if let Some(pf) = self.pathfinder.as_mut() {
+            pf.remove_flow_field(key);
+        }
+    }
+    
+    // Make a tribe follow a flow field when not hunting (weight <= 0 disables)
+    pub fn set_tribe_flow(&mut self, tribe: u16, key: u32, weight: f32) {
+        if let Some(pf) = self.pathfinder.as_mut() {
+            pf.set_tribe_flow(tribe, key, weight);
+        }
+    }
+    
+    // Flow field direction at a point for debugging; returns [dx, dy, cost] or empty
+    pub fn flow_direction(&self, key: u32, x: f32, y: f32) -> Vec<f32> {
+        let (Some(map), Some(pf)) = (self.collision_map.as_ref(), self.pathfinder.as_ref()) else {
+            return Vec::new();
+        };
+        
+        pf.flow_field(key)
+            .and_then(|field| {
+                field
+                    .direction(map, x, y)
+                    .map(|(dx, dy)| vec![dx, dy, field.distance(map, x, y)])
+            })
+            .unwrap_or_default()
+    }
+    
+    // Strength of alarm propagation between herd mates (0 disables, 1 = undamped)
+    pub fn set_herd_panic(&mut self, strength: f32) {
+        self.herd_panic = strength.clamp(0.0, 1.0);
+    }
+    
+    // Default weight of a steering behavior by name (0 disables); false if unknown
+    pub fn set_behavior_weight(&mut self, name: &str, weight: f32) -> bool {
+        self.steering.set_weight(name, weight)
+    }
+    
+    // Per-tribe weight override for a steering behavior; false if unknown
+    pub fn set_tribe_behavior_weight(&mut self, tribe: u16, name: &str, weight: f32) -> bool {
+        self.steering.set_tribe_weight(tribe, name, weight)
+    }
+    
+    pub fn clear_tribe_behavior_weights(&mut self, tribe: u16) {
+        self.steering.clear_tribe_weights(tribe);
+    }
+    
+    // Apply a scenario steering config: { weights: { name: w }, tribes: [{ tribe, weights }] }
+    pub fn configure_steering(&mut self, config: JsValue) -> Result<(), JsValue> {
+        let config: steering::SteeringConfig = serde_wasm_bindgen::from_value(config)?;
+        self.steering
+            .apply_config(&config)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+    
+    pub fn get_steering_config(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.steering.config())?)
+    }
+    
+    // Copy the current food grid (cols x rows, same layout as the JS FoodSystem buffer)
+    pub fn load_food_grid(&mut self, food: &[f32], cols: usize, rows: usize) {
+        let grid = self.food_grid.get_or_insert_with(|| {
+            food::FoodGrid::new(cols, rows, self.world_width, self.world_height)
+        });
+        
+        if grid.cols() != cols || grid.rows() != rows {
+            *grid = food::FoodGrid::new(cols, rows, self.world_width, self.world_height);
+        }
+        
+        grid.load(food);
+    }
+    
+    // Fill a fresh food grid to `capacity` per cell, regrowing regen × capacity per second.
+    // Only for runs without the JS FoodSystem (it owns food otherwise).
+    pub fn init_food_grid(&mut self, cols: usize, rows: usize, capacity: f32, regen: f32) {
+        let mut grid = food::FoodGrid::new(cols, rows, self.world_width, self.world_height);
+        grid.fill(capacity, regen);
+        self.food_grid = Some(grid);
+    }
+    
+    pub fn grow_food(&mut self, dt: f32) {
+        if let Some(grid) = self.food_grid.as_mut() {
+            grid.regrow(dt);
+        }
+    }
+    
+    // Let entities eat the food cell they stand on (JS consumeAt rules); returns meals eaten
+    pub fn graze_batch(&mut self, start_idx: usize, end_idx: usize) -> u32 {
+        let Some(grid) = self.food_grid.as_mut() else {
+            return 0;
+        };
+        let end = end_idx.min(self.count);
+        let mut meals = 0;
+        
+        for i in start_idx..end {
+            if self.alive[i] == 0 {
+                continue;
+            }
+            let min_food = food::grazing_threshold(self.genes.food_standards(i));
+            let consumed = grid.consume_at(self.pos_x[i], self.pos_y[i], min_food);
+            if consumed > 0.0 {
+                let gain = energy::plant_energy(consumed, self.genes.diet(i));
+                self.energy[i] = (self.energy[i] + gain).min(self.energy_config.max_energy);
+                meals += 1;
+            }
+        }
+        
+        meals
+    }
+    
+    // One complete single-threaded tick in worker order (movement, physics, combat, grazing,
+    // upkeep, reproduction, food regrowth). Used by headless runs; the JS workers drive the
+    // batch methods themselves.
+    pub fn step(&mut self, dt: f32) {
+        let count = self.count;
+        self.rebuild_spatial_hash();
+        self.process_movement_batch(0, count, dt);
+        self.integrate_physics_batch(0, count, dt);
+        self.resolve_combat_batch(0, count, dt);
+        self.graze_batch(0, count);
+        self.update_energy_batch(0, count, dt);
+        self.reproduce_batch(0, self.count, dt);
+        self.grow_food(dt);
+        self.finish_tick(dt);
+    }
+    
+    // Recorded history between two sim times as CSV (same resolution rule as query_history)
+    pub fn export_history_csv(&self, from: f64, to: f64) -> String {
+        export::history_csv(&self.recorder.query(from, to), &self.gene_registry)
+    }
+    
+    // Same table as an Arrow IPC file
+    #[cfg(feature = "arrow")]
+    pub fn export_history_arrow(&self, from: f64, to: f64) -> Result<Vec<u8>, String> {
+        export::history_arrow(&self.recorder.query(from, to), &self.gene_registry)
+    }
+    
+    // Ticks between automatic entity dumps (0 disables them)
+    pub fn set_entity_dump_interval(&mut self, ticks: u32) {
+        self.entity_dump_interval = ticks as u64;
+    }
+    
+    // Append every living entity (id, tribe, position, energy, age, genes) to the dump table;
+    // no-op if this tick was already dumped
+    pub fn dump_entities(&mut self) {
+        if self.entity_dumps.last_tick() == Some(self.tick) {
+            return;
+        }
+        for i in 0..self.count {
+            if self.alive[i] == 0 {
+                continue;
+            }
+            self.entity_dumps.push(
+                self.tick,
+                self.entity_id[i],
+                self.tribe_id[i],
+                self.pos_x[i],
+                self.pos_y[i],
+                self.energy[i],
+                self.age[i],
+                self.genes.entity(i),
+            );
+        }
+    }
+    
+    // Rows in the dump table
+    pub fn entity_dump_len(&self) -> usize {
+        self.entity_dumps.len()
+    }
+    
+    pub fn clear_entity_dumps(&mut self) {
+        self.entity_dumps.clear();
+    }
+    
+    pub fn export_entities_csv(&self) -> String {
+        export::entities_csv(&self.entity_dumps, &self.gene_registry)
+    }
+    
+    #[cfg(feature = "arrow")]
+    pub fn export_entities_arrow(&self) -> Result<Vec<u8>, String> {
+        export::entities_arrow(&self.entity_dumps, &self.gene_registry)
+    }
+    
+    // Load data from SharedArrayBuffers (for initialization). `gene_stride` is the number of
+    // genes per entity in `genes` (9 for the JS layout); 0 leaves the genes untouched.
+    #[allow(clippy::too_many_arguments)]
     pub fn load_from_buffers(
         &mut self,
         pos_x: &[f32],
@@ -162,6 +1256,7 @@ impl SimCore {
         alive: &[u8],
         tribe_id: &[u16],
         genes: &[f32],
+        gene_stride: usize,
     ) {
         let count = pos_x.len().min(self.capacity);
         
@@ -173,8 +1268,19 @@ impl SimCore {
         self.alive[..count].copy_from_slice(&alive[..count]);
         self.tribe_id[..count].copy_from_slice(&tribe_id[..count]);
         
-        let gene_count = (count * 9).min(self.genes.len());
-        self.genes[..gene_count].copy_from_slice(&genes[..gene_count]);
+        // Buffers with fewer genes per entity (e.g. the 9-gene JS layout) fill the leading
+        // genes and keep registry defaults for the rest
+        if gene_stride > 0 {
+            self.genes.load(genes, gene_stride, count);
+        }
+        
+        // Entities the core hasn't seen yet are recorded as founders
+        for i in 0..count {
+            if self.alive[i] != 0 && self.entity_id[i] == 0 {
+                self.entity_id[i] =
+                    self.lineage.record_birth([lineage::NO_PARENT; 2], self.tribe_id[i], self.tick);
+            }
+        }
         
         self.count = count;
     }
@@ -196,8 +1302,439 @@ impl SimCore {
     }
 }
 
+// Rust API for native runs (types that don't cross the JS boundary)
+impl SimCore {
+    // Recorded sample times and the summed population of `tribes` at each
+    pub fn population_series(&self, tribes: &[u16], from: f64, to: f64) -> (Vec<f64>, Vec<f64>) {
+        self.recorder
+            .query(from, to)
+            .iter()
+            .map(|sample| {
+                let count: u32 = tribes
+                    .iter()
+                    .filter_map(|&t| sample.population.get(t as usize))
+                    .sum();
+                (sample.time, count as f64)
+            })
+            .unzip()
+    }
+    
+    pub fn population_report(
+        &self,
+        prey_tribes: &[u16],
+        predator_tribes: &[u16],
+        from: f64,
+        to: f64,
+        config: &analysis::AnalysisConfig,
+    ) -> analysis::PopulationReport {
+        let (times, prey) = self.population_series(prey_tribes, from, to);
+        let (_, predators) = self.population_series(predator_tribes, from, to);
+        analysis::analyze(&times, &prey, &predators, config)
+    }
+}
+
+// Internal helpers (not exported to JS)
+impl SimCore {
+    fn build(
+        capacity: usize,
+        world_width: f32,
+        world_height: f32,
+        cell_size: f32,
+        registry: genes::GeneRegistry,
+    ) -> Self {
+        log!("Initializing SimCore with capacity: {}", capacity);
+        let genes_per_entity = registry.len();
+        
+        SimCore {
+            pos_x: vec![0.0; capacity],
+            pos_y: vec![0.0; capacity],
+            vel_x: vec![0.0; capacity],
+            vel_y: vec![0.0; capacity],
+            energy: vec![50.0; capacity],
+            age: vec![0.0; capacity],
+            panic: vec![0.0; capacity],
+            crowd_stress: vec![0.0; capacity],
+            hunt_target: vec![-1; capacity],
+            pack_bonus: vec![0.0; capacity],
+            nearby_allies: vec![0; capacity],
+            nearby_enemies: vec![0; capacity],
+            enemy_strength: vec![0.0; capacity],
+            rival: vec![-1; capacity],
+            behavior: behavior::BehaviorStates::new(capacity),
+            brains: brain::Brains::new(capacity),
+            alive: vec![0; capacity],
+            tribe_id: vec![0; capacity],
+            genes: genes::Genes::new(&registry, capacity),
+            gene_registry: registry,
+            spatial_hash: spatial_hash::SpatialHash::new(world_width, world_height, cell_size, capacity),
+            collision_map: None,
+            pathfinder: None,
+            los_budget: 8,
+            food_grid: None,
+            herd_panic: 0.0,
+            steering: steering::SteeringPipeline::default(),
+            energy_config: energy::EnergyConfig::default(),
+            reproduction_config: reproduction::ReproductionConfig::default(),
+            mated: vec![false; capacity],
+            deaths: Vec::new(),
+            rng: rng::Rng::new(DEFAULT_SEED),
+            world_width,
+            world_height,
+            capacity,
+            count: 0,
+            tick: 0,
+            time: 0.0,
+            counters: stats::Counters::default(),
+            tribe_info: Vec::new(),
+            selection: evolution::SelectionTracker::new(genes_per_entity),
+            recorder: recorder::Recorder::new(recorder::RecorderConfig::default()),
+            recorded_totals: stats::TribeCounters::default(),
+            species: species::SpeciesTracker::new(capacity, DEFAULT_SPECIES_THRESHOLD),
+            species_interval: DEFAULT_SPECIES_INTERVAL,
+            entity_id: vec![0; capacity],
+            lineage: lineage::Lineage::default(),
+            lineage_prune_interval: DEFAULT_LINEAGE_PRUNE_INTERVAL,
+            entity_dumps: export::EntityTable::default(),
+            entity_dump_interval: 0,
+            events: events::EventQueue::default(),
+            last_biome: vec![events::NO_BIOME; capacity],
+            follow: None,
+        }
+    }
+    
+    // Place a child of `parent` and `mate` in the free slot `child`
+    fn spawn_offspring(&mut self, child: usize, parent: usize, mate: usize) {
+        let config = self.reproduction_config;
+        
+        self.genes.crossover(&self.gene_registry, child, parent, mate, config.crossover, &mut self.rng);
+        self.genes.mutate(&self.gene_registry, child, config.mutation_intensity, &mut self.rng);
+        self.brains.copy(parent, child);
+        self.brains.mutate(child, BRAIN_MUTATION_RATE, BRAIN_MUTATION_SCALE, &mut self.rng);
+        
+        // Between the parents, slightly offset
+        let offset = 10.0 + self.rng.next_f32() * 15.0;
+        let angle = self.rng.next_f32() * std::f32::consts::TAU;
+        let mid_x = (self.pos_x[parent] + self.pos_x[mate]) * 0.5 + angle.cos() * offset;
+        let mid_y = (self.pos_y[parent] + self.pos_y[mate]) * 0.5 + angle.sin() * offset;
+        self.pos_x[child] = mid_x.rem_euclid(self.world_width);
+        self.pos_y[child] = mid_y.rem_euclid(self.world_height);
+        
+        let heading = self.rng.next_f32() * std::f32::consts::TAU;
+        let speed = self.genes.max_speed(child) * 0.5;
+        self.vel_x[child] = heading.cos() * speed;
+        self.vel_y[child] = heading.sin() * speed;
+        
+        self.energy[parent] -= config.parent_cost;
+        self.energy[mate] -= config.parent_cost;
+        self.energy[child] = config.offspring_energy;
+        self.age[child] = 0.0;
+        self.tribe_id[child] = self.tribe_id[parent];
+        self.panic[child] = 0.0;
+        self.crowd_stress[child] = 0.0;
+        self.hunt_target[child] = -1;
+        self.pack_bonus[child] = 0.0;
+        self.rival[child] = -1;
+        self.behavior.reset(child);
+        self.alive[child] = 1;
+        let parents = [self.entity_id[parent], self.entity_id[mate]];
+        self.entity_id[child] = self.lineage.record_birth(parents, self.tribe_id[child], self.tick);
+        self.counters.birth(self.tribe_id[child]);
+        self.push_birth(child, parents);
+        self.refresh_selection_baseline();
+        self.selection.record(
+            self.tribe_id[child],
+            &[self.genes.entity(parent), self.genes.entity(mate)],
+            self.genes.entity(child),
+        );
+        
+        self.mated[parent] = true;
+        self.mated[mate] = true;
+        self.mated[child] = true;
+        self.count = self.count.max(child + 1);
+    }
+    
+    fn entity_target(&self, target: i32, from: [f32; 2]) -> Option<inspect::EntityTarget> {
+        let j = usize::try_from(target).ok().filter(|&j| j < self.count && self.alive[j] != 0)?;
+        let position = [self.pos_x[j], self.pos_y[j]];
+        Some(inspect::EntityTarget::new(j, self.entity_id[j], self.tribe_id[j], position, from))
+    }
+    
+    fn inspection(&self, i: usize) -> inspect::Inspection {
+        let position = [self.pos_x[i], self.pos_y[i]];
+        let alive = self.alive[i] != 0;
+        let species = self.species.species_of(i);
+        
+        let mate = if alive && self.reproduction_config.sexual {
+            reproduction::find_mate(
+                i,
+                &self.pos_x,
+                &self.pos_y,
+                &self.energy,
+                &self.alive,
+                &self.mated,
+                &self.tribe_id,
+                &self.genes,
+                &self.gene_registry,
+                &self.spatial_hash,
+                &self.reproduction_config,
+            )
+        } else {
+            None
+        };
+        
+        let food = self.behavior.food_target[i].zip(self.food_grid.as_ref()).map(|((x, y), grid)| {
+            let (col, row) = grid.cell_of(x, y);
+            inspect::FoodTarget {
+                col: col as u32,
+                row: row as u32,
+                position: [x, y],
+                food: grid.at(col, row),
+                distance: inspect::distance(position, [x, y]),
+            }
+        });
+        
+        let mut neighbors = inspect::Neighbors {
+            allies: self.nearby_allies[i],
+            enemies: self.nearby_enemies[i],
+            ..Default::default()
+        };
+        if alive {
+            let vision = self.genes.vision(i);
+            self.spatial_hash.for_each_neighbor(position[0], position[1], vision, |j| {
+                if j == i
+                    || self.alive[j] == 0
+                    || inspect::distance(position, [self.pos_x[j], self.pos_y[j]]) > vision
+                {
+                    return;
+                }
+                neighbors.in_vision += 1;
+                if self.tribe_id[j] == self.tribe_id[i] {
+                    neighbors.same_tribe += 1;
+                }
+                if species != species::NO_SPECIES && self.species.species_of(j) == species {
+                    neighbors.same_species += 1;
+                }
+            });
+        }
+        
+        let id = self.entity_id[i];
+        let lineage = self.lineage.get(id).map(|record| inspect::LineageInfo {
+            parents: record.parents.into_iter().filter(|&p| p != lineage::NO_PARENT).collect(),
+            birth_tick: record.birth_tick as u32,
+            generation: inspect::generation(&self.lineage, id),
+            living_children: (0..self.count)
+                .filter(|&j| self.alive[j] != 0)
+                .filter_map(|j| self.lineage.get(self.entity_id[j]))
+                .filter(|child| child.parents.contains(&id))
+                .count() as u32,
+        });
+        
+        inspect::Inspection {
+            index: i as u32,
+            id,
+            alive,
+            tribe: self.tribe_id[i],
+            species: (species != species::NO_SPECIES).then_some(species),
+            position,
+            velocity: [self.vel_x[i], self.vel_y[i]],
+            speed: self.vel_x[i].hypot(self.vel_y[i]),
+            energy: self.energy[i],
+            age: self.age[i],
+            panic: self.panic[i],
+            crowd_stress: self.crowd_stress[i],
+            pack_bonus: self.pack_bonus[i],
+            enemy_strength: self.enemy_strength[i],
+            neural: self.brains.controls(self.tribe_id[i]),
+            genes: self
+                .gene_registry
+                .specs()
+                .iter()
+                .zip(self.genes.entity(i))
+                .map(|(spec, &value)| (spec.name.clone(), value))
+                .collect(),
+            behavior: inspect::BehaviorInfo {
+                state: self.behavior.get(i),
+                timer: self.behavior.timer[i],
+                scarcity: self.behavior.scarcity[i],
+                heading: [self.behavior.heading_x[i], self.behavior.heading_y[i]],
+            },
+            targets: inspect::Targets {
+                prey: self.entity_target(self.hunt_target[i], position),
+                rival: self.entity_target(self.rival[i], position),
+                mate: mate.and_then(|j| self.entity_target(j as i32, position)),
+                food,
+            },
+            neighbors,
+            lineage,
+        }
+    }
+    
+    // Append this tick's frame to the followed entity's trail; stops once it has died
+    fn record_follow_frame(&mut self) {
+        let Some((i, id)) = self.follow.as_ref().filter(|f| f.alive).map(|f| (f.index as usize, f.id)) else {
+            return;
+        };
+        let frame = (self.alive[i] != 0 && self.entity_id[i] == id).then(|| self.follow_frame(i));
+        if let Some(follow) = self.follow.as_mut() {
+            match frame {
+                Some(frame) => follow.push(frame),
+                None => follow.alive = false,
+            }
+        }
+    }
+    
+    fn follow_frame(&self, i: usize) -> inspect::FollowFrame {
+        let target_position = |target: i32| {
+            usize::try_from(target)
+                .ok()
+                .filter(|&j| j < self.count && self.alive[j] != 0)
+                .map(|j| [self.pos_x[j], self.pos_y[j]])
+        };
+        inspect::FollowFrame {
+            tick: self.tick as u32,
+            position: [self.pos_x[i], self.pos_y[i]],
+            velocity: [self.vel_x[i], self.vel_y[i]],
+            energy: self.energy[i],
+            state: self.behavior.get(i),
+            prey: target_position(self.hunt_target[i]),
+            rival: target_position(self.rival[i]),
+            food: self.behavior.food_target[i].map(|(x, y)| [x, y]),
+        }
+    }
+    
+    // Population means the selection differential compares parents against
+    fn refresh_selection_baseline(&mut self) {
+        if self.selection.baseline_due(self.tick) {
+            self.selection.set_baseline(&self.alive, &self.tribe_id, &self.genes, self.count, self.tick);
+        }
+    }
+    
+    fn push_birth(&mut self, child: usize, parents: [u64; 2]) {
+        let event = events::Event::Birth {
+            child: self.entity_id[child],
+            parents,
+            slot: child as u32,
+            tribe: self.tribe_id[child],
+        };
+        self.events.push(self.tick, event);
+        self.last_biome[child] = events::NO_BIOME;
+    }
+    
+    // Queue a BiomeEnter for every living entity whose biome changed since the last tick;
+    // an entity's first observed biome (newborns, founders) is not an event
+    fn track_biomes(&mut self) {
+        let Some(map) = self.collision_map.as_ref() else {
+            return;
+        };
+        for i in 0..self.count {
+            if self.alive[i] == 0 {
+                continue;
+            }
+            let Some(biome) = map.biome_at(self.pos_x[i], self.pos_y[i]) else {
+                return;
+            };
+            let from = self.last_biome[i];
+            if from != biome {
+                self.last_biome[i] = biome;
+                if from != events::NO_BIOME {
+                    let event = events::Event::BiomeEnter {
+                        id: self.entity_id[i],
+                        slot: i as u32,
+                        biome,
+                        from,
+                    };
+                    self.events.push(self.tick, event);
+                }
+            }
+        }
+    }
+    
+    fn record_sample(&mut self) {
+        let stride = self.genes.stride();
+        let mut population: Vec<u32> = Vec::new();
+        let mut gene_sums = vec![0.0f64; stride];
+        let mut living = 0u32;
+        
+        for i in 0..self.count {
+            if self.alive[i] == 0 {
+                continue;
+            }
+            let tribe = self.tribe_id[i] as usize;
+            if tribe >= population.len() {
+                population.resize(tribe + 1, 0);
+            }
+            population[tribe] += 1;
+            living += 1;
+            for (sum, &value) in gene_sums.iter_mut().zip(self.genes.entity(i)) {
+                *sum += value as f64;
+            }
+        }
+        
+        let totals = self.counters.totals();
+        let last = self.recorded_totals;
+        self.recorded_totals = totals;
+        let n = living.max(1) as f64;
+        
+        self.recorder.push(recorder::Sample {
+            tick: self.tick,
+            time: self.time,
+            population,
+            births: totals.births.saturating_sub(last.births),
+            deaths: totals.deaths.saturating_sub(last.deaths),
+            kills: totals.kills.saturating_sub(last.kills),
+            starved: totals.starved.saturating_sub(last.starved),
+            food_total: self.food_grid.as_ref().map_or(0.0, |grid| grid.total()),
+            gene_means: gene_sums.iter().map(|sum| (sum / n) as f32).collect(),
+        });
+    }
+    
+    // `energy` is what the killer gained from the kill
+    fn record_kill(&mut self, killer: usize, victim: usize, energy: f32) {
+        self.counters.kill(self.tribe_id[killer]);
+        let event = events::Event::Kill {
+            killer: self.entity_id[killer],
+            victim: self.entity_id[victim],
+            energy,
+            killer_slot: killer as u32,
+            victim_slot: victim as u32,
+            x: self.pos_x[victim],
+            y: self.pos_y[victim],
+        };
+        self.events.push(self.tick, event);
+        self.mark_dead(victim, DeathCause::Killed);
+    }
+    
+    fn mark_dead(&mut self, index: usize, cause: DeathCause) {
+        self.lineage.record_death(self.entity_id[index], cause, self.tick);
+        self.counters.death(self.tribe_id[index], cause);
+        let event = events::Event::Death {
+            id: self.entity_id[index],
+            slot: index as u32,
+            cause,
+            x: self.pos_x[index],
+            y: self.pos_y[index],
+        };
+        self.events.push(self.tick, event);
+        // End the follow trail here so a recycled slot never continues it
+        if let Some(follow) = self.follow.as_mut().filter(|f| f.id == self.entity_id[index]) {
+            follow.alive = false;
+        }
+        self.last_biome[index] = events::NO_BIOME;
+        self.entity_id[index] = 0;
+        if let Some(pf) = self.pathfinder.as_mut() {
+            pf.forget(index);
+        }
+        self.alive[index] = 0;
+        self.hunt_target[index] = -1;
+        self.pack_bonus[index] = 0.0;
+        self.rival[index] = -1;
+        self.behavior.reset(index);
+        self.panic[index] = 0.0;
+    }
+}
+
 // Initialize WASM module
 #[wasm_bindgen(start)]
 pub fn init() {
-    console::log_1(&"WASM module initialized".into());
+    log!("WASM module initialized");
 }
\ No newline at end of file
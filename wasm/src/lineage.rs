@@ -0,0 +1,186 @@
+// Phylogeny recording
+// Every entity gets a stable 64-bit id that survives slot recycling. Births append a record
+// (parents, birth tick, tribe) to the lineage log and deaths fill in the death tick and cause.
+// Pruning keeps the living entities and their first-parent ancestry (the tree to_newick
+// draws) and drops everything else, so the log follows the genealogy of the current
+// population instead of every breeder that ever lived. Second parents are kept as ids but
+// their records may be pruned. The surviving tree can be exported as Newick or as a compact
+// binary table.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::DeathCause;
+
+// Parent id used for founders
+pub const NO_PARENT: u64 = 0;
+
+const BINARY_MAGIC: &[u8; 4] = b"LIN1";
+// Death tick / cause written for entities still alive
+const ALIVE_TICK: u64 = u64::MAX;
+const ALIVE_CAUSE: u8 = 0xff;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub id: u64,
+    // Founders have [NO_PARENT, NO_PARENT], asexual births a single parent
+    pub parents: [u64; 2],
+    pub tribe: u16,
+    pub birth_tick: u64,
+    pub death_tick: Option<u64>,
+    pub death_cause: Option<DeathCause>,
+}
+
+pub struct Lineage {
+    records: Vec<LineageRecord>,
+    // id -> position in records
+    index: HashMap<u64, usize>,
+    next_id: u64,
+}
+
+impl Default for Lineage {
+    fn default() -> Self {
+        Lineage {
+            records: Vec::new(),
+            index: HashMap::new(),
+            next_id: 1,
+        }
+    }
+}
+
+impl Lineage {
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&LineageRecord> {
+        self.index.get(&id).map(|&k| &self.records[k])
+    }
+
+    // Append a birth and return the new entity id
+    pub fn record_birth(&mut self, parents: [u64; 2], tribe: u16, tick: u64) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.index.insert(id, self.records.len());
+        self.records.push(LineageRecord {
+            id,
+            parents,
+            tribe,
+            birth_tick: tick,
+            death_tick: None,
+            death_cause: None,
+        });
+        id
+    }
+
+    pub fn record_death(&mut self, id: u64, cause: DeathCause, tick: u64) {
+        if let Some(&k) = self.index.get(&id) {
+            let record = &mut self.records[k];
+            if record.death_tick.is_none() {
+                record.death_tick = Some(tick);
+                record.death_cause = Some(cause);
+            }
+        }
+    }
+
+    // Drop dead records that aren't first-parent ancestors of a living entity; returns how
+    // many were removed
+    pub fn prune(&mut self) -> usize {
+        let mut keep: Vec<bool> = self.records.iter().map(|r| r.death_tick.is_none()).collect();
+
+        // Children are always appended after their parents, so one backward pass
+        // carries "has a living descendant" up the first-parent line
+        for k in (0..self.records.len()).rev() {
+            if !keep[k] {
+                continue;
+            }
+            if let Some(&p) = self.index.get(&self.records[k].parents[0]) {
+                keep[p] = true;
+            }
+        }
+
+        let before = self.records.len();
+        let mut flags = keep.into_iter();
+        self.records.retain(|_| flags.next().unwrap_or(false));
+        self.index = self.records.iter().enumerate().map(|(k, r)| (r.id, k)).collect();
+        before - self.records.len()
+    }
+
+    // Newick tree following the first parent of each record. Branch lengths are ticks between
+    // the parent's and the child's birth; records whose parent is unknown become roots.
+    pub fn to_newick(&self, current_tick: u64) -> String {
+        let mut children: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut roots = Vec::new();
+        for (k, record) in self.records.iter().enumerate() {
+            let parent = record.parents[0];
+            if self.index.contains_key(&parent) {
+                children.entry(parent).or_default().push(k);
+            } else {
+                roots.push(k);
+            }
+        }
+
+        let mut out = String::new();
+        if roots.len() > 1 {
+            out.push('(');
+        }
+
+        // Iterative depth-first walk: (record, next child to visit)
+        for (r, &root) in roots.iter().enumerate() {
+            if r > 0 {
+                out.push(',');
+            }
+
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            while let Some((k, next)) = stack.pop() {
+                let record = &self.records[k];
+                let kids = children.get(&record.id).map_or(&[][..], |v| v.as_slice());
+
+                if next < kids.len() {
+                    out.push(if next == 0 { '(' } else { ',' });
+                    stack.push((k, next + 1));
+                    stack.push((kids[next], 0));
+                    continue;
+                }
+
+                if !kids.is_empty() {
+                    out.push(')');
+                }
+                let branch = match self.get(record.parents[0]) {
+                    Some(parent) => record.birth_tick.saturating_sub(parent.birth_tick),
+                    None => record.death_tick.unwrap_or(current_tick).saturating_sub(record.birth_tick),
+                };
+                out.push_str(&format!("{}:{}", record.id, branch));
+            }
+        }
+
+        if roots.len() > 1 {
+            out.push(')');
+        }
+        out.push(';');
+        out
+    }
+
+    // "LIN1", u32 record count, then per record (little endian):
+    // id u64, parent_a u64, parent_b u64, birth_tick u64, death_tick u64 (u64::MAX = alive),
+    // death cause u8 (0xff = alive), tribe u16
+    pub fn to_binary(&self) -> Vec<u8> {
+        const RECORD_BYTES: usize = 8 * 5 + 1 + 2;
+        let mut out = Vec::with_capacity(8 + self.records.len() * RECORD_BYTES);
+        out.extend_from_slice(BINARY_MAGIC);
+        out.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+
+        for record in &self.records {
+            out.extend_from_slice(&record.id.to_le_bytes());
+            out.extend_from_slice(&record.parents[0].to_le_bytes());
+            out.extend_from_slice(&record.parents[1].to_le_bytes());
+            out.extend_from_slice(&record.birth_tick.to_le_bytes());
+            out.extend_from_slice(&record.death_tick.unwrap_or(ALIVE_TICK).to_le_bytes());
+            out.push(record.death_cause.map_or(ALIVE_CAUSE, |c| c as u8));
+            out.extend_from_slice(&record.tribe.to_le_bytes());
+        }
+
+        out
+    }
+}
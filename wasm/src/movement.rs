@@ -1,35 +1,107 @@
+use crate::behavior::{BehaviorInputs, BehaviorStates};
+use crate::brain::{BrainInputs, Brains};
+use crate::collision::BiomeCollisionMap;
+use crate::food::FoodGrid;
+use crate::genes::Genes;
+use crate::pathfinding::Pathfinder;
 use crate::spatial_hash::SpatialHash;
+use crate::steering::{SteeringContext, SteeringPipeline};
+use crate::types::BehaviorState;
 
-const GENES_PER_ENTITY: usize = 9;
 const MAX_NEIGHBORS: usize = 20;
+const ENERGY_MAX: f32 = 100.0;
+// Minimum diet/aggression for an out-of-tribe entity to read as a predator
+const THREAT_MIN_DIET: f32 = 0.2;
+const THREAT_MIN_AGGRESSION: f32 = 0.2;
+// Below this energy hunger overrides fear entirely
+const FEARLESS_ENERGY: f32 = 25.0;
+// Neighbors within vision at which crowd stress saturates
+const CROWD_LIMIT: f32 = 15.0;
+// Crowd stress above which reproduction is blocked
+pub const REPRODUCTION_CROWD_LIMIT: f32 = 0.7;
+// Cohesion above which hunters coordinate as a pack
+const PACK_COHESION: f32 = 0.4;
+// Share of effective speed kept while resting
+const RESTING_SPEED: f32 = 0.3;
 
-// Cached neighbor data to avoid repeated calculations
+// Neighbor within vision, kept for the pack-hunting pass
 struct Neighbor {
     index: usize,
-    dx: f32,
-    dy: f32,
-    dist_sq: f32,
     is_ally: bool,
-    energy: f32,
 }
 
-#[inline]
-fn get_gene(genes: &[f32], entity_idx: usize, gene_idx: usize) -> f32 {
-    genes[entity_idx * GENES_PER_ENTITY + gene_idx]
+// Shared world state for a movement pass
+pub struct MovementEnv<'a> {
+    pub spatial_hash: &'a SpatialHash,
+    pub terrain: Option<&'a BiomeCollisionMap>,
+    pub food: Option<&'a FoodGrid>,
+    pub steering: &'a SteeringPipeline,
+    // Max terrain raycasts per entity (0 disables occlusion)
+    pub los_budget: usize,
+    // How much alarm carries from panicking allies (0 disables herd panic)
+    pub herd_panic: f32,
+    // Neural controllers for tribes that opted out of the steering pipeline
+    pub brains: &'a Brains,
 }
 
+// What one entity sensed during its neighbor pass
+#[derive(Default)]
+pub struct Perception {
+    // Sum of visible ally velocities and positions
+    pub align_x: f32,
+    pub align_y: f32,
+    pub cohesion_x: f32,
+    pub cohesion_y: f32,
+    pub separate_x: f32,
+    pub separate_y: f32,
+    pub nearby_allies: u32,
+    // Other tribes within vision, their summed strength (energy share × (0.5 + aggression))
+    // and the closest one in view
+    pub nearby_enemies: u32,
+    pub enemy_strength: f32,
+    pub nearest_enemy: Option<usize>,
+    // Closest visible ally
+    pub nearest_ally: Option<usize>,
+    // Everyone within vision, and min(1, count / CROWD_LIMIT)
+    pub nearby_count: u32,
+    pub crowd_stress: f32,
+    pub best_prey: Option<usize>,
+    // Allies hunting the same prey, and this entity's rank among them (for flanking)
+    pub pack_size: u32,
+    pub pack_slot: u32,
+    // Direction away from visible predators, weighted by threat
+    pub flee_x: f32,
+    pub flee_y: f32,
+    // Summed predator threat, capped at 1
+    pub threat_level: f32,
+    // Strongest panic among visible allies and that ally's velocity
+    pub ally_panic: f32,
+    pub ally_panic_vx: f32,
+    pub ally_panic_vy: f32,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn process_entity_movement(
     i: usize,
-    pos_x: &mut [f32],
-    pos_y: &mut [f32],
+    pos_x: &[f32],
+    pos_y: &[f32],
     vel_x: &mut [f32],
     vel_y: &mut [f32],
     energy: &[f32],
+    alive: &[u8],
+    panic: &mut [f32],
+    crowd_stress: &mut [f32],
+    hunt_target: &mut [i32],
+    pack_bonus: &mut [f32],
+    nearby_allies: &mut [u16],
+    nearby_enemies: &mut [u16],
+    enemy_strength: &mut [f32],
+    rival: &mut [i32],
+    behavior: &mut BehaviorStates,
     tribe_id: &[u16],
-    genes: &[f32],
-    spatial_hash: &SpatialHash,
-    world_width: f32,
-    world_height: f32,
+    genes: &Genes,
+    env: &MovementEnv,
+    pathfinder: Option<&mut Pathfinder>,
     dt: f32,
 ) {
     let px = pos_x[i];
@@ -38,24 +110,22 @@ pub fn process_entity_movement(
     let my_energy = energy[i];
     
     // Extract genes
-    let speed = get_gene(genes, i, 0);
-    let vision = get_gene(genes, i, 1);
-    let metabolism = get_gene(genes, i, 2);
-    let aggression = get_gene(genes, i, 4);
-    let cohesion = get_gene(genes, i, 5);
-    let food_standards = get_gene(genes, i, 6);
-    let diet = get_gene(genes, i, 7);
-    let view_angle = get_gene(genes, i, 8) * std::f32::consts::PI / 180.0;
-    
-    // Calculate effective speed based on metabolism
-    let metabolism_efficiency = (metabolism / 0.15).min(1.0);
-    let effective_speed = speed * metabolism_efficiency;
+    let vision = genes.vision(i);
+    let cohesion = genes.cohesion(i);
+    let food_standards = genes.food_standards(i);
+    let diet = genes.diet(i);
+    let view_angle = genes.view_angle(i) * std::f32::consts::PI / 180.0;
+    
+    // Effective speed from speed, metabolism and body size
+    let effective_speed = genes.max_speed(i);
+    let my_radius = genes.radius(i);
     
     // Carnivore/herbivore traits
     let carnivore_level = diet.max(0.0);
     let is_hunter = carnivore_level > 0.2;
     let hunting_threshold = 95.0 - (carnivore_level * 35.0);
     let should_hunt = is_hunter && my_energy < hunting_threshold;
+    let pack_hunter = should_hunt && cohesion > PACK_COHESION;
     
     // View direction for cone checks
     let my_orientation = vel_y[i].atan2(vel_x[i]);
@@ -72,20 +142,42 @@ pub fn process_entity_movement(
     let max_vision = vision.max(hunt_vision);
     let vision_sq = vision * vision;
     let hunt_vision_sq = hunt_vision * hunt_vision;
+    let herd_panic = env.herd_panic;
     
     // Collect neighbors
     let mut neighbors = Vec::with_capacity(MAX_NEIGHBORS);
-    let mut align_x = 0.0;
-    let mut align_y = 0.0;
-    let mut separate_x = 0.0;
-    let mut separate_y = 0.0;
-    let mut cohesion_x = 0.0;
-    let mut cohesion_y = 0.0;
-    let mut nearby_allies = 0;
-    let mut best_prey: Option<usize> = None;
+    let mut perception = Perception::default();
     let mut best_prey_score = f32::MAX;
+    // Target of the nearest pack mate already on a hunt
+    let mut pack_target: Option<usize> = None;
+    let mut pack_target_dist_sq = f32::MAX;
+    let mut nearest_enemy_dist_sq = f32::MAX;
+    let mut nearest_ally_dist_sq = f32::MAX;
+    let mut ally_candidates: Vec<(usize, f32, f32, f32)> = Vec::new();
+    
+    // Terrain line-of-sight, bounded to los_budget raycasts per entity. Once the budget is
+    // spent, anything beyond the neighboring cell counts as hidden. Prey, threats and rivals
+    // are checked during the scan; allies are deferred so flocking only gets the rays left over.
+    let mut rays_left = env.los_budget;
+    let mut has_line_of_sight = |tx: f32, ty: f32| -> bool {
+        // A zero budget turns occlusion off
+        let Some(map) = env.terrain.filter(|_| env.los_budget > 0) else {
+            return true;
+        };
+        let dx = tx - px;
+        let dy = ty - py;
+        let cell = map.cell_size();
+        if dx * dx + dy * dy < cell * cell {
+            return true;
+        }
+        if rays_left == 0 {
+            return false;
+        }
+        rays_left -= 1;
+        map.line_of_sight(px, py, tx, ty)
+    };
     
-    spatial_hash.for_each_neighbor_limited(px, py, max_vision, MAX_NEIGHBORS * 2, |j| {
+    env.spatial_hash.for_each_neighbor_limited(px, py, max_vision, MAX_NEIGHBORS * 2, |j| {
         if j == i {
             return false;
         }
@@ -109,42 +201,63 @@ pub fn process_entity_movement(
         
         let is_ally = tribe_id[j] == my_tribe;
         
+        if dist_sq < vision_sq {
+            perception.nearby_count += 1;
+        }
+        
         // Store neighbor if within normal vision
         if dist_sq < vision_sq && neighbors.len() < MAX_NEIGHBORS {
-            neighbors.push(Neighbor {
-                index: j,
-                dx,
-                dy,
-                dist_sq,
-                is_ally,
-                energy: energy[j],
-            });
+            neighbors.push(Neighbor { index: j, is_ally });
         }
         
-        // Flocking calculations for allies
+        // Allies in vision wait for the rays prey and threats leave over
         if is_ally && dist_sq < vision_sq {
-            nearby_allies += 1;
-            
-            // Alignment
-            align_x += vel_x[j];
-            align_y += vel_y[j];
+            ally_candidates.push((j, dx, dy, dist_sq));
+        }
+        
+        // Enemy presence for group support and territorial clashes
+        if !is_ally && dist_sq < vision_sq {
+            perception.nearby_enemies += 1;
+            perception.enemy_strength += (energy[j] / ENERGY_MAX).clamp(0.0, 1.0)
+                * (0.5 + genes.aggression(j));
             
-            // Cohesion
-            cohesion_x += pos_x[j];
-            cohesion_y += pos_y[j];
+            if in_view && dist_sq < nearest_enemy_dist_sq && has_line_of_sight(pos_x[j], pos_y[j]) {
+                nearest_enemy_dist_sq = dist_sq;
+                perception.nearest_enemy = Some(j);
+            }
+        }
+        
+        // Threat detection: carnivorous, aggressive strangers in sight
+        if !is_hunter && !is_ally && dist_sq < vision_sq {
+            let their_carnivore = genes.diet(j).max(0.0);
+            let their_aggression = genes.aggression(j);
             
-            // Separation (stronger for closer entities)
-            if dist_sq < 400.0 && dist_sq > 0.0001 {
-                let sep_force = 1.0 / dist_sq.sqrt();
-                separate_x -= dx * sep_force;
-                separate_y -= dy * sep_force;
+            if their_carnivore > THREAT_MIN_DIET
+                && their_aggression > THREAT_MIN_AGGRESSION
+                && has_line_of_sight(pos_x[j], pos_y[j])
+            {
+                let dist = dist_sq.sqrt() + 0.1;
+                let proximity = 1.0 - (dist / vision).min(1.0);
+                let threat = their_carnivore * (0.5 + their_aggression) * proximity;
+                
+                perception.flee_x -= (dx / dist) * threat;
+                perception.flee_y -= (dy / dist) * threat;
+                perception.threat_level += threat;
             }
         }
         
+        // Panicking allies spread alarm through the herd
+        if herd_panic > 0.0 && is_ally && dist_sq < vision_sq && panic[j] > perception.ally_panic {
+            perception.ally_panic = panic[j];
+            perception.ally_panic_vx = vel_x[j];
+            perception.ally_panic_vy = vel_y[j];
+        }
+        
         // Hunting logic
         if should_hunt && !is_ally && in_view {
-            let their_energy = energy[j];
-            let their_speed = get_gene(genes, j, 0);
+            // Bigger prey carry more energy
+            let their_energy = energy[j] * genes.size(j);
+            let their_speed = genes.max_speed(j);
             
             // Score based on distance and prey value
             let catch_probability = if their_speed > 0.0 {
@@ -155,77 +268,250 @@ pub fn process_entity_movement(
             
             let score = dist_sq / (their_energy * catch_probability + 1.0);
             
-            if score < best_prey_score {
+            if score < best_prey_score && has_line_of_sight(pos_x[j], pos_y[j]) {
                 best_prey_score = score;
-                best_prey = Some(j);
+                perception.best_prey = Some(j);
             }
         }
         
         true
     });
     
-    // Calculate steering forces
-    let mut steer_x = 0.0;
-    let mut steer_y = 0.0;
-    
-    // Flocking behaviors for allies
-    if nearby_allies > 0 {
-        // Alignment
-        if nearby_allies > 1 {
-            align_x /= nearby_allies as f32;
-            align_y /= nearby_allies as f32;
-            let align_mag = (align_x * align_x + align_y * align_y).sqrt();
-            if align_mag > 0.001 {
-                steer_x += (align_x / align_mag) * cohesion * 0.5;
-                steer_y += (align_y / align_mag) * cohesion * 0.5;
-            }
+    // Flocking calculations for allies in sight, nearest first
+    ally_candidates.sort_by(|a, b| a.3.total_cmp(&b.3));
+    for (j, dx, dy, dist_sq) in ally_candidates {
+        if !has_line_of_sight(pos_x[j], pos_y[j]) {
+            continue;
+        }
+        perception.nearby_allies += 1;
+        
+        if dist_sq < nearest_ally_dist_sq {
+            nearest_ally_dist_sq = dist_sq;
+            perception.nearest_ally = Some(j);
         }
         
+        // Alignment
+        perception.align_x += vel_x[j];
+        perception.align_y += vel_y[j];
+        
         // Cohesion
-        cohesion_x = (cohesion_x / nearby_allies as f32) - px;
-        cohesion_y = (cohesion_y / nearby_allies as f32) - py;
-        let cohesion_mag = (cohesion_x * cohesion_x + cohesion_y * cohesion_y).sqrt();
-        if cohesion_mag > 0.001 {
-            steer_x += (cohesion_x / cohesion_mag) * cohesion * 0.3;
-            steer_y += (cohesion_y / cohesion_mag) * cohesion * 0.3;
+        perception.cohesion_x += pos_x[j];
+        perception.cohesion_y += pos_y[j];
+        
+        // Pack mates already on a hunt share their target
+        if pack_hunter && hunt_target[j] >= 0 && dist_sq < pack_target_dist_sq {
+            pack_target = Some(hunt_target[j] as usize);
+            pack_target_dist_sq = dist_sq;
+        }
+        
+        // Separation (stronger for closer entities) within the pair's combined personal space
+        let personal_space = (my_radius + genes.radius(j)) * 0.5;
+        if dist_sq < personal_space * personal_space && dist_sq > 0.0001 {
+            let sep_force = 1.0 / dist_sq.sqrt();
+            perception.separate_x -= dx * sep_force;
+            perception.separate_y -= dy * sep_force;
         }
     }
     
-    // Separation (always active)
-    let sep_mag = (separate_x * separate_x + separate_y * separate_y).sqrt();
-    if sep_mag > 0.001 {
-        steer_x += (separate_x / sep_mag) * 2.0;
-        steer_y += (separate_y / sep_mag) * 2.0;
+    // Join the pack's hunt if its target is still alive and within reach
+    if let Some(target) = pack_target {
+        let tx = pos_x[target] - px;
+        let ty = pos_y[target] - py;
+        if target != i
+            && alive[target] != 0
+            && tribe_id[target] != my_tribe
+            && tx * tx + ty * ty < hunt_vision_sq
+        {
+            perception.best_prey = Some(target);
+        }
     }
     
-    // Hunting behavior
-    if let Some(prey_idx) = best_prey {
-        let prey_dx = pos_x[prey_idx] - px;
-        let prey_dy = pos_y[prey_idx] - py;
-        let prey_dist = (prey_dx * prey_dx + prey_dy * prey_dy).sqrt();
-        
-        if prey_dist > 0.001 {
-            // Strong pursuit force
-            let hunger_desperation = ((hunting_threshold - my_energy) / hunting_threshold).max(0.0);
-            let hunt_force = 3.0 + hunger_desperation * 2.0;
-            steer_x += (prey_dx / prey_dist) * hunt_force;
-            steer_y += (prey_dy / prey_dist) * hunt_force;
+    // Count pack mates on the same prey and our rank among them
+    if let (true, Some(target)) = (pack_hunter, perception.best_prey) {
+        for n in &neighbors {
+            if n.is_ally && hunt_target[n.index] == target as i32 {
+                perception.pack_size += 1;
+                if n.index < i {
+                    perception.pack_slot += 1;
+                }
+            }
         }
     }
     
-    // Add some random wandering
-    let wander_angle = ((i as f32 * 12.34 + px * 56.78) % 1.0) * std::f32::consts::TAU;
-    steer_x += wander_angle.cos() * 0.1;
-    steer_y += wander_angle.sin() * 0.1;
+    hunt_target[i] = perception.best_prey.map_or(-1, |t| t as i32);
+    pack_bonus[i] = if perception.pack_size > 0 {
+        (perception.pack_size as f32 * 0.2).min(1.0) * cohesion
+    } else {
+        0.0
+    };
+    
+    nearby_allies[i] = perception.nearby_allies.min(u16::MAX as u32) as u16;
+    nearby_enemies[i] = perception.nearby_enemies.min(u16::MAX as u32) as u16;
+    enemy_strength[i] = perception.enemy_strength;
+    rival[i] = perception.nearest_enemy.map_or(-1, |r| r as i32);
+    
+    perception.threat_level = perception.threat_level.min(1.0);
+    perception.crowd_stress = (perception.nearby_count as f32 / CROWD_LIMIT).min(1.0);
+    crowd_stress[i] = perception.crowd_stress;
+    
+    // Hunger makes prey bolder, starving prey ignore danger
+    let bravery = if my_energy < FEARLESS_ENERGY {
+        0.0
+    } else {
+        (my_energy / ENERGY_MAX).clamp(0.3, 1.0)
+    };
+    
+    // Panic decays by herd_panic with each hop from the threat
+    panic[i] = if perception.threat_level > 0.0 {
+        perception.threat_level * bravery
+    } else {
+        perception.ally_panic * herd_panic * bravery
+    };
+    
+    // Decide what this entity is doing before steering
+    let satiation = (my_energy / ENERGY_MAX).clamp(0.0, 1.0);
+    let ally_center_dir = if perception.nearby_allies > 0 {
+        let n = perception.nearby_allies as f32;
+        let cx = perception.cohesion_x / n - px;
+        let cy = perception.cohesion_y / n - py;
+        let mag = (cx * cx + cy * cy).sqrt();
+        (mag > 0.001).then(|| (cx / mag, cy / mag))
+    } else {
+        None
+    };
+    let state = behavior.update(
+        i,
+        &BehaviorInputs {
+            satiation,
+            food_standards,
+            local_food: env.food.map(|grid| grid.at_world(px, py)),
+            is_hunter,
+            should_hunt,
+            has_prey: perception.best_prey.is_some(),
+            panic: panic[i],
+            crowd_stress: perception.crowd_stress,
+            view_dir: (view_dir_x, view_dir_y),
+            ally_center_dir,
+        },
+        dt,
+    );
+    
+    // Calculate steering forces, from the tribe's evolved brain if it has one
+    let (steer_x, steer_y) = if env.brains.controls(my_tribe) {
+        let inputs = brain_inputs(
+            px,
+            py,
+            vision,
+            satiation,
+            effective_speed,
+            (view_dir_x, view_dir_y),
+            view_cos_threshold,
+            &perception,
+            pos_x,
+            pos_y,
+            genes,
+            env,
+        );
+        behavior.food_target[i] = None;
+        env.brains.think(i, &inputs)
+    } else {
+        let mut ctx = SteeringContext {
+            index: i,
+            px,
+            py,
+            tribe: my_tribe,
+            energy: my_energy,
+            satiation,
+            effective_speed,
+            vision,
+            cohesion,
+            food_standards,
+            diet,
+            view_dir: (view_dir_x, view_dir_y),
+            view_cos_threshold,
+            hunting_threshold,
+            bravery,
+            herd_panic,
+            state,
+            migration_heading: behavior.heading(i),
+            perception: &perception,
+            pos_x,
+            pos_y,
+            terrain: env.terrain,
+            food: env.food,
+            pathfinder,
+            food_target: None,
+        };
+        let force = env.steering.steer(&mut ctx);
+        behavior.food_target[i] = ctx.food_target;
+        force
+    };
     
     // Apply steering to velocity
     vel_x[i] += steer_x * dt * 10.0;
     vel_y[i] += steer_y * dt * 10.0;
     
-    // Clamp velocity to max speed
+    // Clamp velocity to max speed (resting entities amble)
+    let max_speed = if state == BehaviorState::Resting {
+        effective_speed * RESTING_SPEED
+    } else {
+        effective_speed
+    };
     let vel_mag = (vel_x[i] * vel_x[i] + vel_y[i] * vel_y[i]).sqrt();
-    if vel_mag > effective_speed {
-        vel_x[i] = (vel_x[i] / vel_mag) * effective_speed;
-        vel_y[i] = (vel_y[i] / vel_mag) * effective_speed;
+    if vel_mag > max_speed {
+        vel_x[i] = (vel_x[i] / vel_mag) * max_speed;
+        vel_y[i] = (vel_y[i] / vel_mag) * max_speed;
+    }
+}
+
+// Sensor readings for the neural controller: relative vectors scaled by vision
+#[allow(clippy::too_many_arguments)]
+fn brain_inputs(
+    px: f32,
+    py: f32,
+    vision: f32,
+    satiation: f32,
+    effective_speed: f32,
+    view_dir: (f32, f32),
+    view_cos_threshold: f32,
+    perception: &Perception,
+    pos_x: &[f32],
+    pos_y: &[f32],
+    genes: &Genes,
+    env: &MovementEnv,
+) -> BrainInputs {
+    let inv_vision = 1.0 / vision.max(1.0);
+    let relative = |j: usize| ((pos_x[j] - px) * inv_vision, (pos_y[j] - py) * inv_vision);
+    let mut inputs = BrainInputs {
+        satiation,
+        ..Default::default()
+    };
+
+    if let Some(grid) = env.food {
+        let radius_cells = (vision / grid.min_cell_extent()).ceil() as i32;
+        if let Some((fx, fy, food)) =
+            grid.best_patch(px, py, radius_cells, vision, view_dir, view_cos_threshold, 0.0)
+        {
+            inputs.food = ((fx - px) * inv_vision, (fy - py) * inv_vision);
+            inputs.food_amount = food;
+        }
+    }
+
+    if let Some(ally) = perception.nearest_ally {
+        inputs.ally = relative(ally);
+    }
+
+    if let Some(enemy) = perception.nearest_enemy {
+        inputs.enemy = relative(enemy);
+        inputs.enemy_threat = genes.diet(enemy).max(0.0) * genes.aggression(enemy);
+    }
+
+    if let Some(map) = env.terrain {
+        let lookahead = effective_speed.max(map.cell_size() * 1.5);
+        if !map.is_world_traversable(px + view_dir.0 * lookahead, py + view_dir.1 * lookahead) {
+            inputs.blocked_ahead = 1.0;
+        }
     }
-}
\ No newline at end of file
+
+    inputs
+}
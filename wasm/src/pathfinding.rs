@@ -0,0 +1,522 @@
+// Grid pathfinding over the biome traversability map
+// A* for point-to-point queries, cached flow fields for shared goals (food areas, tribe homes).
+// The steering hook keeps each entity's last A* path and only replans every REPLAN_INTERVAL
+// calls, when the goal drifts off the path's end, or when the map changes.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::collision::BiomeCollisionMap;
+
+const SQRT_2: f32 = std::f32::consts::SQRT_2;
+const DEFAULT_MAX_EXPANSIONS: usize = 2048;
+// How many waypoints ahead the steering hook looks for a clear shortcut
+const STEER_LOOKAHEAD: usize = 4;
+// Steering calls a cached path is followed for before A* runs again
+const REPLAN_INTERVAL: u32 = 15;
+// Cells the goal may move away from the cached path's end before it is replanned
+const REPLAN_GOAL_SLACK: f32 = 2.0;
+
+// 8-connected neighborhood: (dx, dy, step cost)
+const NEIGHBORS: [(i32, i32, f32); 8] = [
+    (1, 0, 1.0),
+    (-1, 0, 1.0),
+    (0, 1, 1.0),
+    (0, -1, 1.0),
+    (1, 1, SQRT_2),
+    (1, -1, SQRT_2),
+    (-1, 1, SQRT_2),
+    (-1, -1, SQRT_2),
+];
+
+#[inline]
+fn wrap(c: i32, n: usize) -> usize {
+    c.rem_euclid(n as i32) as usize
+}
+
+// Shortest distance between two cell coordinates on a toroidal axis
+#[inline]
+fn wrapped_delta(a: usize, b: usize, n: usize) -> f32 {
+    let d = a.abs_diff(b);
+    d.min(n - d) as f32
+}
+
+// Octile distance heuristic (admissible for 8-connected grids)
+#[inline]
+fn octile(map: &BiomeCollisionMap, a: (usize, usize), b: (usize, usize)) -> f32 {
+    let dx = wrapped_delta(a.0, b.0, map.grid_width());
+    let dy = wrapped_delta(a.1, b.1, map.grid_height());
+    dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+}
+
+// Resolve a neighbor step, rejecting blocked cells and diagonal corner cutting
+#[inline]
+fn step(map: &BiomeCollisionMap, cx: usize, cy: usize, dx: i32, dy: i32) -> Option<(usize, usize)> {
+    let w = map.grid_width();
+    let h = map.grid_height();
+    let nx = wrap(cx as i32 + dx, w);
+    let ny = wrap(cy as i32 + dy, h);
+
+    if !map.is_cell_traversable(nx, ny) {
+        return None;
+    }
+
+    if dx != 0 && dy != 0
+        && (!map.is_cell_traversable(nx, cy) || !map.is_cell_traversable(cx, ny))
+    {
+        return None;
+    }
+
+    Some((nx, ny))
+}
+
+// Check that the straight segment between two world points stays on traversable cells
+pub fn line_clear(map: &BiomeCollisionMap, x0: f32, y0: f32, x1: f32, y1: f32) -> bool {
+    let (dx, dy) = map.world_delta(x0, y0, x1, y1);
+    let dist = (dx * dx + dy * dy).sqrt();
+    let step_len = map.cell_size() * 0.5;
+    let steps = (dist / step_len).ceil().max(1.0) as usize;
+
+    for s in 1..=steps {
+        let t = s as f32 / steps as f32;
+        let (cx, cy) = map.world_to_cell(x0 + dx * t, y0 + dy * t);
+        if !map.is_cell_traversable(cx, cy) {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[derive(Clone, Copy)]
+struct OpenNode {
+    f: f32,
+    g: f32,
+    cell: u32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.f == other.f
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    // Reversed so BinaryHeap pops the lowest f first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f.total_cmp(&self.f)
+    }
+}
+
+// Reusable A* scratch buffers (generation stamps avoid clearing per query)
+struct PathSearch {
+    g_score: Vec<f32>,
+    came_from: Vec<u32>,
+    stamp: Vec<u32>,
+    generation: u32,
+    open: BinaryHeap<OpenNode>,
+}
+
+impl PathSearch {
+    fn new(cells: usize) -> Self {
+        PathSearch {
+            g_score: vec![f32::INFINITY; cells],
+            came_from: vec![u32::MAX; cells],
+            stamp: vec![0; cells],
+            generation: 0,
+            open: BinaryHeap::new(),
+        }
+    }
+
+    fn begin(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.stamp.fill(0);
+            self.generation = 1;
+        }
+        self.open.clear();
+    }
+
+    #[inline]
+    fn g(&self, idx: usize) -> f32 {
+        if self.stamp[idx] == self.generation {
+            self.g_score[idx]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize, g: f32, from: u32) {
+        self.stamp[idx] = self.generation;
+        self.g_score[idx] = g;
+        self.came_from[idx] = from;
+    }
+
+    // A* between two cells; returns the cell sequence from start to goal (inclusive)
+    fn run(
+        &mut self,
+        map: &BiomeCollisionMap,
+        start: (usize, usize),
+        goal: (usize, usize),
+        max_expansions: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let w = map.grid_width();
+
+        // The start cell may be blocked if the entity is hugging a coast; the goal may not
+        if !map.is_cell_traversable(goal.0, goal.1) {
+            return None;
+        }
+
+        self.begin();
+
+        let start_idx = start.1 * w + start.0;
+        let goal_idx = goal.1 * w + goal.0;
+        self.set(start_idx, 0.0, u32::MAX);
+        self.open.push(OpenNode {
+            f: octile(map, start, goal),
+            g: 0.0,
+            cell: start_idx as u32,
+        });
+
+        let mut expansions = 0;
+
+        while let Some(node) = self.open.pop() {
+            let idx = node.cell as usize;
+
+            // Stale heap entry
+            if node.g > self.g(idx) {
+                continue;
+            }
+
+            if idx == goal_idx {
+                let mut path = Vec::new();
+                let mut cur = node.cell;
+                while cur != u32::MAX {
+                    let c = cur as usize;
+                    path.push((c % w, c / w));
+                    cur = self.came_from[c];
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            expansions += 1;
+            if expansions > max_expansions {
+                return None;
+            }
+
+            let cx = idx % w;
+            let cy = idx / w;
+
+            for &(dx, dy, cost) in NEIGHBORS.iter() {
+                if let Some((nx, ny)) = step(map, cx, cy, dx, dy) {
+                    let n_idx = ny * w + nx;
+                    let tentative = node.g + cost;
+                    if tentative < self.g(n_idx) {
+                        self.set(n_idx, tentative, node.cell);
+                        self.open.push(OpenNode {
+                            f: tentative + octile(map, (nx, ny), goal),
+                            g: tentative,
+                            cell: n_idx as u32,
+                        });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Cost-to-goal field over the whole grid, built with multi-source Dijkstra
+pub struct FlowField {
+    dist: Vec<f32>,
+    goals: Vec<(f32, f32)>,
+    generation: u32,
+}
+
+impl FlowField {
+    pub fn build(map: &BiomeCollisionMap, goals: &[(f32, f32)]) -> Self {
+        let w = map.grid_width();
+        let h = map.grid_height();
+        let mut dist = vec![f32::INFINITY; w * h];
+        let mut open = BinaryHeap::new();
+
+        for &(gx, gy) in goals {
+            let (cx, cy) = map.world_to_cell(gx, gy);
+            if !map.is_cell_traversable(cx, cy) {
+                continue;
+            }
+            let idx = cy * w + cx;
+            dist[idx] = 0.0;
+            open.push(OpenNode { f: 0.0, g: 0.0, cell: idx as u32 });
+        }
+
+        while let Some(node) = open.pop() {
+            let idx = node.cell as usize;
+            if node.g > dist[idx] {
+                continue;
+            }
+
+            let cx = idx % w;
+            let cy = idx / w;
+
+            for &(dx, dy, cost) in NEIGHBORS.iter() {
+                if let Some((nx, ny)) = step(map, cx, cy, dx, dy) {
+                    let n_idx = ny * w + nx;
+                    let tentative = node.g + cost;
+                    if tentative < dist[n_idx] {
+                        dist[n_idx] = tentative;
+                        open.push(OpenNode { f: tentative, g: tentative, cell: n_idx as u32 });
+                    }
+                }
+            }
+        }
+
+        FlowField {
+            dist,
+            goals: goals.to_vec(),
+            generation: map.generation(),
+        }
+    }
+
+    // Remaining path cost (in cells) from a world position, INFINITY if unreachable
+    pub fn distance(&self, map: &BiomeCollisionMap, x: f32, y: f32) -> f32 {
+        let (cx, cy) = map.world_to_cell(x, y);
+        self.dist[cy * map.grid_width() + cx]
+    }
+
+    // Unit world-space direction toward the lowest-cost neighbor cell
+    pub fn direction(&self, map: &BiomeCollisionMap, x: f32, y: f32) -> Option<(f32, f32)> {
+        let w = map.grid_width();
+        let (cx, cy) = map.world_to_cell(x, y);
+        let here = self.dist[cy * w + cx];
+
+        if here == 0.0 {
+            return None;
+        }
+
+        let mut best = here;
+        let mut best_step: Option<(i32, i32)> = None;
+
+        for &(dx, dy, _) in NEIGHBORS.iter() {
+            if let Some((nx, ny)) = step(map, cx, cy, dx, dy) {
+                let d = self.dist[ny * w + nx];
+                if d < best {
+                    best = d;
+                    best_step = Some((dx, dy));
+                }
+            }
+        }
+
+        // Grid Y runs opposite to world Y
+        best_step.map(|(dx, dy)| {
+            let len = ((dx * dx + dy * dy) as f32).sqrt();
+            (dx as f32 / len, -dy as f32 / len)
+        })
+    }
+}
+
+// Flow field a tribe follows, and how strongly
+#[derive(Clone, Copy)]
+pub struct TribeFlow {
+    pub key: u32,
+    pub weight: f32,
+}
+
+// Path one entity is following, with the state deciding when it goes stale
+struct CachedPath {
+    waypoints: Vec<(f32, f32)>,
+    goal: (usize, usize),
+    generation: u32,
+    age: u32,
+}
+
+pub struct Pathfinder {
+    search: PathSearch,
+    // Keyed by entity slot
+    paths: HashMap<usize, CachedPath>,
+    fields: HashMap<u32, FlowField>,
+    tribe_flows: HashMap<u16, TribeFlow>,
+    max_expansions: usize,
+}
+
+impl Pathfinder {
+    pub fn new(map: &BiomeCollisionMap) -> Self {
+        Pathfinder {
+            search: PathSearch::new(map.grid_width() * map.grid_height()),
+            paths: HashMap::new(),
+            fields: HashMap::new(),
+            tribe_flows: HashMap::new(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions.max(1);
+    }
+
+    // A* between two world points; returns world-space waypoints (cell centers)
+    pub fn find_path(
+        &mut self,
+        map: &BiomeCollisionMap,
+        from_x: f32,
+        from_y: f32,
+        to_x: f32,
+        to_y: f32,
+    ) -> Option<Vec<(f32, f32)>> {
+        let start = map.world_to_cell(from_x, from_y);
+        let goal = map.world_to_cell(to_x, to_y);
+
+        self.search
+            .run(map, start, goal, self.max_expansions)
+            .map(|cells| cells.into_iter().map(|(cx, cy)| map.cell_to_world(cx, cy)).collect())
+    }
+
+    // Steering hook for `entity`: direct heading when the line is clear, otherwise toward the
+    // next waypoint of its cached A* path
+    pub fn steer_toward(
+        &mut self,
+        map: &BiomeCollisionMap,
+        entity: usize,
+        from_x: f32,
+        from_y: f32,
+        to_x: f32,
+        to_y: f32,
+    ) -> Option<(f32, f32)> {
+        let (tx, ty) = if line_clear(map, from_x, from_y, to_x, to_y) {
+            self.paths.remove(&entity);
+            (to_x, to_y)
+        } else {
+            let path = self.cached_path(map, entity, from_x, from_y, to_x, to_y)?;
+
+            // Skip waypoints already passed: resume from the one nearest the entity
+            let here = map.world_to_cell(from_x, from_y);
+            let start = path
+                .iter()
+                .take(STEER_LOOKAHEAD * 2)
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    let da = octile(map, here, map.world_to_cell(a.0, a.1));
+                    let db = octile(map, here, map.world_to_cell(b.0, b.1));
+                    da.total_cmp(&db)
+                })
+                .map_or(0, |(k, _)| k);
+            let path = &path[start..];
+
+            // Furthest waypoint within the lookahead that can be reached in a straight line
+            let mut target = path[path.len().min(2) - 1];
+            for &wp in path.iter().skip(2).take(STEER_LOOKAHEAD - 1) {
+                if !line_clear(map, from_x, from_y, wp.0, wp.1) {
+                    break;
+                }
+                target = wp;
+            }
+            target
+        };
+
+        let (dx, dy) = map.world_delta(from_x, from_y, tx, ty);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len > 0.001 {
+            Some((dx / len, dy / len))
+        } else {
+            None
+        }
+    }
+
+    // Drop the cached path of an entity that died or left its slot
+    pub fn forget(&mut self, entity: usize) {
+        self.paths.remove(&entity);
+    }
+
+    // The entity's path toward the goal, replanned when it is old, the goal moved away from
+    // its end, or the traversability map changed
+    fn cached_path(
+        &mut self,
+        map: &BiomeCollisionMap,
+        entity: usize,
+        from_x: f32,
+        from_y: f32,
+        to_x: f32,
+        to_y: f32,
+    ) -> Option<&[(f32, f32)]> {
+        let goal = map.world_to_cell(to_x, to_y);
+        let stale = self.paths.get(&entity).is_none_or(|cached| {
+            cached.age >= REPLAN_INTERVAL
+                || cached.generation != map.generation()
+                || octile(map, cached.goal, goal) > REPLAN_GOAL_SLACK * SQRT_2
+        });
+
+        if stale {
+            let Some(waypoints) = self.find_path(map, from_x, from_y, to_x, to_y) else {
+                self.paths.remove(&entity);
+                return None;
+            };
+            let cached = CachedPath { waypoints, goal, generation: map.generation(), age: 0 };
+            self.paths.insert(entity, cached);
+        }
+
+        let cached = self.paths.get_mut(&entity)?;
+        cached.age += 1;
+        Some(&cached.waypoints)
+    }
+
+    pub fn set_flow_field(&mut self, map: &BiomeCollisionMap, key: u32, goals: &[(f32, f32)]) {
+        self.fields.insert(key, FlowField::build(map, goals));
+    }
+
+    pub fn remove_flow_field(&mut self, key: u32) {
+        self.fields.remove(&key);
+        self.tribe_flows.retain(|_, flow| flow.key != key);
+    }
+
+    pub fn flow_field(&self, key: u32) -> Option<&FlowField> {
+        self.fields.get(&key)
+    }
+
+    // Bind a tribe to a flow field; a non-positive weight removes the binding
+    pub fn set_tribe_flow(&mut self, tribe: u16, key: u32, weight: f32) {
+        if weight > 0.0 {
+            self.tribe_flows.insert(tribe, TribeFlow { key, weight });
+        } else {
+            self.tribe_flows.remove(&tribe);
+        }
+    }
+
+    // Flow direction and weight for an entity of the given tribe, if its tribe follows a field
+    pub fn tribe_flow_direction(
+        &self,
+        map: &BiomeCollisionMap,
+        tribe: u16,
+        x: f32,
+        y: f32,
+    ) -> Option<(f32, f32, f32)> {
+        let flow = self.tribe_flows.get(&tribe)?;
+        let field = self.fields.get(&flow.key)?;
+        field
+            .direction(map, x, y)
+            .map(|(dx, dy)| (dx, dy, flow.weight))
+    }
+
+    // Rebuild cached fields after the traversability map changed
+    pub fn refresh(&mut self, map: &BiomeCollisionMap) {
+        if map.grid_width() * map.grid_height() != self.search.g_score.len() {
+            self.search = PathSearch::new(map.grid_width() * map.grid_height());
+        }
+
+        for field in self.fields.values_mut() {
+            if field.generation != map.generation() {
+                *field = FlowField::build(map, &field.goals);
+            }
+        }
+    }
+}
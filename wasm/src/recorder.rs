@@ -0,0 +1,205 @@
+// Time-series recorder with hierarchical downsampling
+// Every sample lands in level 0. Each time a level has received `factor` new samples they are
+// merged into one sample on the next level, so level k covers factor^k times the span of
+// level 0 at the same fixed capacity. Memory stays bounded no matter how long a run lasts.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderConfig {
+    // Ticks between samples (0 disables recording)
+    pub interval: u32,
+    // Samples kept per level
+    pub capacity: usize,
+    // Samples merged into one on the next level
+    pub factor: usize,
+    pub levels: usize,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        RecorderConfig {
+            interval: 30,
+            capacity: 512,
+            factor: 4,
+            levels: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sample {
+    // Tick and sim time at the end of the sampled span
+    pub tick: u64,
+    pub time: f64,
+    // Living entities per tribe id
+    pub population: Vec<u32>,
+    // Events during the sampled span
+    pub births: u32,
+    pub deaths: u32,
+    pub kills: u32,
+    pub starved: u32,
+    pub food_total: f32,
+    // Mean of every registry gene over the living population
+    pub gene_means: Vec<f32>,
+}
+
+impl Sample {
+    // One sample standing for several consecutive ones: event counts add up,
+    // levels (population, food, genes) are averaged
+    fn merge(samples: &[Sample]) -> Sample {
+        let n = samples.len().max(1) as f32;
+        let last = samples.last().cloned().unwrap_or_default();
+        let tribes = samples.iter().map(|s| s.population.len()).max().unwrap_or(0);
+        let mut merged = Sample {
+            tick: last.tick,
+            time: last.time,
+            population: vec![0; tribes],
+            gene_means: vec![0.0; last.gene_means.len()],
+            ..Default::default()
+        };
+
+        let mut population = vec![0.0f32; tribes];
+        for s in samples {
+            for (total, &p) in population.iter_mut().zip(&s.population) {
+                *total += p as f32;
+            }
+            for (total, &g) in merged.gene_means.iter_mut().zip(&s.gene_means) {
+                *total += g / n;
+            }
+            merged.births += s.births;
+            merged.deaths += s.deaths;
+            merged.kills += s.kills;
+            merged.starved += s.starved;
+            merged.food_total += s.food_total / n;
+        }
+        for (out, total) in merged.population.iter_mut().zip(population) {
+            *out = (total / n).round() as u32;
+        }
+
+        merged
+    }
+}
+
+struct Level {
+    samples: VecDeque<Sample>,
+    // New samples since the last roll-up into the next level
+    pending: usize,
+    // Whether the oldest samples have been dropped (otherwise the level covers the whole run)
+    evicted: bool,
+}
+
+impl Level {
+    fn reaches_back_to(&self, from: f64) -> bool {
+        match self.samples.front() {
+            Some(first) => !self.evicted || first.time <= from,
+            None => false,
+        }
+    }
+}
+
+pub struct Recorder {
+    config: RecorderConfig,
+    levels: Vec<Level>,
+}
+
+impl Recorder {
+    pub fn new(config: RecorderConfig) -> Self {
+        let config = RecorderConfig {
+            capacity: config.capacity.max(1),
+            factor: config.factor.max(2),
+            levels: config.levels.max(1),
+            ..config
+        };
+        let levels = (0..config.levels)
+            .map(|_| Level {
+                samples: VecDeque::with_capacity(config.capacity),
+                pending: 0,
+                evicted: false,
+            })
+            .collect();
+
+        Recorder { config, levels }
+    }
+
+    pub fn config(&self) -> &RecorderConfig {
+        &self.config
+    }
+
+    #[inline]
+    pub fn due(&self, tick: u64) -> bool {
+        self.config.interval > 0 && tick.is_multiple_of(self.config.interval as u64)
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(|l| l.samples.len()).sum()
+    }
+
+    pub fn clear(&mut self) {
+        for level in &mut self.levels {
+            level.samples.clear();
+            level.pending = 0;
+            level.evicted = false;
+        }
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        let mut carry = Some(sample);
+        let factor = self.config.factor;
+        let capacity = self.config.capacity;
+        let level_count = self.levels.len();
+
+        for (k, level) in self.levels.iter_mut().enumerate() {
+            let Some(sample) = carry.take() else {
+                break;
+            };
+
+            if level.samples.len() == capacity {
+                level.samples.pop_front();
+                level.evicted = true;
+            }
+            level.samples.push_back(sample);
+            level.pending += 1;
+
+            if level.pending >= factor && k + 1 < level_count {
+                level.pending = 0;
+                let recent: Vec<Sample> = level.samples.iter().rev().take(factor).rev().cloned().collect();
+                carry = Some(Sample::merge(&recent));
+            }
+        }
+    }
+
+    // Samples covering from..to (sim seconds) from the finest level that reaches back to
+    // `from` (the coarsest level if none does)
+    pub fn query(&self, from: f64, to: f64) -> Vec<Sample> {
+        let level = self
+            .levels
+            .iter()
+            .find(|l| l.reaches_back_to(from))
+            .or_else(|| self.levels.iter().rev().find(|l| !l.samples.is_empty()));
+
+        // A sample is stamped at the end of its span, so the first one past `to` still
+        // covers the tail of the range
+        level.map_or_else(Vec::new, |l| {
+            let mut out = Vec::new();
+            for s in l.samples.iter().filter(|s| s.time >= from) {
+                out.push(s.clone());
+                if s.time >= to {
+                    break;
+                }
+            }
+            out
+        })
+    }
+
+    // Everything recorded at one level, oldest first
+    pub fn level(&self, k: usize) -> Vec<Sample> {
+        self.levels
+            .get(k)
+            .map_or_else(Vec::new, |l| l.samples.iter().cloned().collect())
+    }
+}
@@ -0,0 +1,118 @@
+// Sexual reproduction: mate search, compatibility and offspring placement
+// Optional alternative to the asexual copy-and-mutate reproduction in the JS worker. Entities
+// above the energy threshold look for the nearest willing mate within vision; pairs whose
+// normalized genetic distance exceeds the compatibility threshold cannot breed, so diverging
+// populations become reproductively isolated.
+
+use serde::{Deserialize, Serialize};
+
+use crate::genes::{Crossover, GeneRegistry, Genes};
+use crate::rng::Rng;
+use crate::spatial_hash::SpatialHash;
+
+// Neighbors examined per mate search
+const MAX_MATE_CANDIDATES: usize = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReproductionConfig {
+    // Sexual mode on/off (off leaves reproduction to the JS worker)
+    pub sexual: bool,
+    // Both partners need more energy than this
+    pub energy_threshold: f32,
+    // Energy each parent gives up, and the child starts with
+    pub parent_cost: f32,
+    pub offspring_energy: f32,
+    pub crossover: Crossover,
+    // Mutation strength in units of each gene's mutation_scale
+    pub mutation_intensity: f32,
+    // Max normalized genetic distance between compatible partners
+    pub compatibility_threshold: f32,
+    // Allow mates from other tribes (still subject to compatibility)
+    pub cross_tribe: bool,
+    // Mating chance multiplier: × (1 - aggression × aggression_penalty)
+    pub aggression_penalty: f32,
+}
+
+impl Default for ReproductionConfig {
+    // Thresholds follow the JS energy config (repro 60, start 50, child at 70% start)
+    fn default() -> Self {
+        ReproductionConfig {
+            sexual: false,
+            energy_threshold: 60.0,
+            parent_cost: 15.0,
+            offspring_energy: 35.0,
+            crossover: Crossover::Uniform,
+            mutation_intensity: 1.0,
+            compatibility_threshold: 0.25,
+            cross_tribe: false,
+            aggression_penalty: 0.8,
+        }
+    }
+}
+
+// Whether `i` tries to mate this tick: repro_chance per second, damped by aggression
+pub fn wants_to_mate(
+    i: usize,
+    energy: &[f32],
+    genes: &Genes,
+    config: &ReproductionConfig,
+    rng: &mut Rng,
+    dt: f32,
+) -> bool {
+    if energy[i] <= config.energy_threshold {
+        return false;
+    }
+
+    let willingness = (1.0 - genes.aggression(i) * config.aggression_penalty).max(0.0);
+    rng.next_f32() < genes.repro_chance(i) * willingness * dt
+}
+
+// Nearest compatible partner for `i` within its vision, if any
+#[allow(clippy::too_many_arguments)]
+pub fn find_mate(
+    i: usize,
+    pos_x: &[f32],
+    pos_y: &[f32],
+    energy: &[f32],
+    alive: &[u8],
+    already_mated: &[bool],
+    tribe_id: &[u16],
+    genes: &Genes,
+    registry: &GeneRegistry,
+    spatial_hash: &SpatialHash,
+    config: &ReproductionConfig,
+) -> Option<usize> {
+    let px = pos_x[i];
+    let py = pos_y[i];
+    let vision = genes.vision(i);
+    let vision_sq = vision * vision;
+    let mut best: Option<usize> = None;
+    let mut best_dist_sq = f32::MAX;
+
+    spatial_hash.for_each_neighbor_limited(px, py, vision, MAX_MATE_CANDIDATES, |j| {
+        if j == i || alive[j] == 0 || already_mated[j] || energy[j] <= config.energy_threshold {
+            return false;
+        }
+        if !config.cross_tribe && tribe_id[j] != tribe_id[i] {
+            return false;
+        }
+
+        let dx = pos_x[j] - px;
+        let dy = pos_y[j] - py;
+        let dist_sq = dx * dx + dy * dy;
+        if dist_sq > vision_sq {
+            return false;
+        }
+
+        if dist_sq < best_dist_sq
+            && registry.distance(genes.entity(i), genes.entity(j)) <= config.compatibility_threshold
+        {
+            best_dist_sq = dist_sq;
+            best = Some(j);
+        }
+        true
+    });
+
+    best
+}
@@ -0,0 +1,44 @@
+// SFC32 PRNG - same algorithm and seeding as src/sim/random.ts
+
+pub struct Rng {
+    a: u32,
+    b: u32,
+    c: u32,
+    d: u32,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        Rng {
+            a: seed,
+            b: seed ^ 0x9e37_79b9,
+            c: seed ^ 0x85eb_ca6b,
+            d: seed ^ 0xc2b2_ae35,
+        }
+    }
+
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let t = self.a.wrapping_add(self.b);
+        self.a = self.b ^ (self.b >> 9);
+        self.b = self.c.wrapping_add(self.c << 3);
+        self.c = self.c.rotate_left(21);
+        self.d = self.d.wrapping_add(1);
+        let t = t.wrapping_add(self.d);
+        self.c = self.c.wrapping_add(t);
+        t
+    }
+
+    // Uniform in [0, 1)
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() as f64 / 4_294_967_296.0) as f32
+    }
+
+    // Standard normal sample (Box-Muller)
+    pub fn next_gaussian(&mut self) -> f32 {
+        let u1 = self.next_f32().max(f32::MIN_POSITIVE);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
@@ -0,0 +1,245 @@
+// Self-contained run description for headless simulations
+// A scenario fixes everything the JS worker would otherwise provide: world size, food grid,
+// tribes and their starting genes, and the core's configs. Read from JSON by the native
+// runner (snake_case keys, every field optional):
+//
+//   {
+//     "seed": 7, "ticks": 36000, "dt": 0.0167,
+//     "world": { "width": 4000, "height": 4000 },
+//     "food": { "cols": 128, "rows": 128, "capacity": 1.5, "regen": 0.05 },
+//     "tribes": [
+//       { "count": 600, "x": 1000, "y": 2000, "radius": 400, "genes": { "diet": -0.8 } },
+//       { "count": 80, "x": 3000, "y": 2000, "radius": 300, "genes": { "diet": 0.9, "aggression": 0.8 } }
+//     ],
+//     "energy": { "base_cost": 1.2 }, "reproduction": { "mutation_intensity": 1.5 }
+//   }
+//
+// There is no asexual path outside the JS worker, so scenarios always use sexual reproduction.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::brain;
+use crate::energy::EnergyConfig;
+use crate::genes::{GeneRegistry, GeneSpec};
+use crate::lineage;
+use crate::recorder::{Recorder, RecorderConfig};
+use crate::reproduction::ReproductionConfig;
+use crate::SimCore;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct WorldConfig {
+    width: f32,
+    height: f32,
+    cell_size: f32,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        WorldConfig {
+            width: 4000.0,
+            height: 4000.0,
+            cell_size: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct FoodConfig {
+    cols: usize,
+    rows: usize,
+    // Food per cell when full, and fraction of it regrown per second
+    capacity: f32,
+    regen: f32,
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        FoodConfig {
+            cols: 128,
+            rows: 128,
+            capacity: 1.5,
+            regen: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct TribeConfig {
+    count: usize,
+    // Spawn disc
+    x: f32,
+    y: f32,
+    radius: f32,
+    // Starting genes by registry name; unlisted genes use the registry default
+    genes: HashMap<String, f32>,
+    // Mutation applied to each founder, in units of each gene's mutation_scale
+    variation: f32,
+    // Steer with an evolvable neural controller instead of the steering pipeline
+    neural: bool,
+}
+
+impl Default for TribeConfig {
+    fn default() -> Self {
+        TribeConfig {
+            count: 100,
+            x: 2000.0,
+            y: 2000.0,
+            radius: 300.0,
+            genes: HashMap::new(),
+            variation: 1.0,
+            neural: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    seed: u32,
+    capacity: usize,
+    ticks: u64,
+    dt: f32,
+    start_energy: f32,
+    world: WorldConfig,
+    food: FoodConfig,
+    // Custom gene layout (default: core genes plus size)
+    genes: Option<Vec<GeneSpec>>,
+    tribes: Vec<TribeConfig>,
+    energy: EnergyConfig,
+    reproduction: ReproductionConfig,
+    recorder: RecorderConfig,
+    // Ticks between full-population dumps for export (0 = only the final state)
+    entity_dump_interval: u64,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Scenario {
+            seed: 1,
+            capacity: 10000,
+            ticks: 18000,
+            dt: 1.0 / 60.0,
+            start_energy: 50.0,
+            world: WorldConfig::default(),
+            food: FoodConfig::default(),
+            genes: None,
+            tribes: vec![TribeConfig::default()],
+            energy: EnergyConfig::default(),
+            reproduction: ReproductionConfig::default(),
+            recorder: RecorderConfig::default(),
+            entity_dump_interval: 0,
+        }
+    }
+}
+
+impl Scenario {
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn set_ticks(&mut self, ticks: u64) {
+        self.ticks = ticks;
+    }
+
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    // Fresh core with the scenario's configs, food grid and founders in place
+    pub fn build(&self) -> Result<SimCore, String> {
+        let registry = match &self.genes {
+            Some(specs) => GeneRegistry::new(specs.clone())?,
+            None => GeneRegistry::default(),
+        };
+
+        let mut sim = SimCore::build(
+            self.capacity,
+            self.world.width,
+            self.world.height,
+            self.world.cell_size,
+            registry,
+        );
+        sim.set_seed(self.seed);
+        sim.energy_config = self.energy;
+        sim.reproduction_config = ReproductionConfig {
+            sexual: true,
+            ..self.reproduction
+        };
+        sim.recorder = Recorder::new(self.recorder);
+        sim.entity_dump_interval = self.entity_dump_interval;
+        sim.init_food_grid(self.food.cols, self.food.rows, self.food.capacity, self.food.regen);
+
+        for (tribe, config) in self.tribes.iter().enumerate() {
+            let tribe = tribe as u16;
+            let overrides = config
+                .genes
+                .iter()
+                .map(|(name, &value)| {
+                    sim.gene_registry
+                        .index_of(name)
+                        .map(|g| (g, value))
+                        .ok_or_else(|| format!("tribe {}: unknown gene '{}'", tribe, name))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            sim.set_tribe_neural(tribe, config.neural);
+            for _ in 0..config.count {
+                let i = sim.count;
+                if i >= sim.capacity {
+                    return Err(format!("capacity {} too small for the founders", self.capacity));
+                }
+                self.spawn_founder(&mut sim, i, tribe, config, &overrides);
+                sim.count += 1;
+            }
+        }
+
+        Ok(sim)
+    }
+
+    fn spawn_founder(
+        &self,
+        sim: &mut SimCore,
+        i: usize,
+        tribe: u16,
+        config: &TribeConfig,
+        overrides: &[(usize, f32)],
+    ) {
+        // Uniform over the spawn disc
+        let angle = sim.rng.next_f32() * std::f32::consts::TAU;
+        let distance = config.radius * sim.rng.next_f32().sqrt();
+        sim.pos_x[i] = (config.x + angle.cos() * distance).rem_euclid(sim.world_width);
+        sim.pos_y[i] = (config.y + angle.sin() * distance).rem_euclid(sim.world_height);
+        sim.vel_x[i] = 0.0;
+        sim.vel_y[i] = 0.0;
+
+        for &(g, value) in overrides {
+            let spec = &sim.gene_registry.specs()[g];
+            sim.genes.set(i, g, value.clamp(spec.min, spec.max));
+        }
+        if config.variation > 0.0 {
+            sim.genes.mutate(&sim.gene_registry, i, config.variation, &mut sim.rng);
+        }
+        if config.neural {
+            sim.brains.randomize(i, brain::INITIAL_WEIGHT_SCALE, &mut sim.rng);
+        }
+
+        sim.energy[i] = self.start_energy;
+        sim.age[i] = 0.0;
+        sim.tribe_id[i] = tribe;
+        sim.alive[i] = 1;
+        sim.entity_id[i] = sim.lineage.record_birth([lineage::NO_PARENT; 2], tribe, 0);
+    }
+}
@@ -29,6 +29,9 @@ impl SpatialHash {
     
     #[inline]
     fn get_key(&self, x: f32, y: f32) -> usize {
+        // Positions not yet wrapped into the world (e.g. loaded from JS) bucket where they wrap to
+        let x = x.rem_euclid(self.width);
+        let y = y.rem_euclid(self.height);
         let cx = ((x / self.cell_size) as usize).min(self.cols - 1);
         let cy = ((y / self.cell_size) as usize).min(self.rows - 1);
         cy * self.cols + cx
@@ -0,0 +1,152 @@
+// Species tracking by genetic-distance clustering (NEAT-style)
+// Every update each living entity joins the first species whose representative genome is
+// within the compatibility threshold, trying its previous species first so identities stay
+// stable. Entities that fit nowhere found a new species. Representatives drift to the
+// centroid of their members; species left without members go extinct.
+
+use serde::{Deserialize, Serialize};
+
+use crate::genes::{GeneRegistry, Genes};
+
+// Entities not yet assigned to a species
+pub const NO_SPECIES: u32 = 0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Species {
+    pub id: u32,
+    pub members: u32,
+    // Tick the species was first seen
+    pub founded: u64,
+    // Centroid genome of the members at the last update
+    pub representative: Vec<f32>,
+}
+
+// Species born and lost since the events were last taken
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeciesEvents {
+    pub births: Vec<u32>,
+    pub extinctions: Vec<u32>,
+}
+
+pub struct SpeciesTracker {
+    // Max normalized genetic distance to a species representative
+    threshold: f32,
+    species: Vec<Species>,
+    // Species id per entity slot (NO_SPECIES if none)
+    membership: Vec<u32>,
+    next_id: u32,
+    events: SpeciesEvents,
+    // Per-species gene sums used to recompute centroids
+    sums: Vec<Vec<f32>>,
+}
+
+impl SpeciesTracker {
+    pub fn new(capacity: usize, threshold: f32) -> Self {
+        SpeciesTracker {
+            threshold,
+            species: Vec::new(),
+            membership: vec![NO_SPECIES; capacity],
+            next_id: 1,
+            events: SpeciesEvents::default(),
+            sums: Vec::new(),
+        }
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold.max(0.0);
+    }
+
+    #[inline]
+    pub fn species_of(&self, i: usize) -> u32 {
+        self.membership[i]
+    }
+
+    pub fn membership(&self) -> &[u32] {
+        &self.membership
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    // Species born since the last take_events, oldest first
+    pub fn pending_births(&self) -> &[u32] {
+        &self.events.births
+    }
+
+    pub fn take_events(&mut self) -> SpeciesEvents {
+        std::mem::take(&mut self.events)
+    }
+
+    // Re-cluster all living entities
+    pub fn update(&mut self, genes: &Genes, registry: &GeneRegistry, alive: &[u8], count: usize, tick: u64) {
+        for s in &mut self.species {
+            s.members = 0;
+        }
+        self.sums.iter_mut().for_each(|sum| sum.fill(0.0));
+
+        for (i, &is_alive) in alive.iter().enumerate().take(count) {
+            if is_alive == 0 {
+                self.membership[i] = NO_SPECIES;
+                continue;
+            }
+
+            let genome = genes.entity(i);
+            let compatible =
+                |s: &Species| registry.distance(genome, &s.representative) <= self.threshold;
+
+            let slot = self
+                .species
+                .iter()
+                .position(|s| s.id == self.membership[i])
+                .filter(|&k| compatible(&self.species[k]))
+                .or_else(|| self.species.iter().position(compatible));
+
+            let slot = match slot {
+                Some(k) => k,
+                None => {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.species.push(Species {
+                        id,
+                        members: 0,
+                        founded: tick,
+                        representative: genome.to_vec(),
+                    });
+                    self.sums.push(vec![0.0; genome.len()]);
+                    self.events.births.push(id);
+                    self.species.len() - 1
+                }
+            };
+
+            self.species[slot].members += 1;
+            for (sum, value) in self.sums[slot].iter_mut().zip(genome) {
+                *sum += value;
+            }
+            self.membership[i] = self.species[slot].id;
+        }
+
+        // Move representatives to their centroids and drop empty species
+        for (s, sum) in self.species.iter_mut().zip(&self.sums) {
+            if s.members > 0 {
+                let n = s.members as f32;
+                for (rep, total) in s.representative.iter_mut().zip(sum) {
+                    *rep = total / n;
+                }
+            } else {
+                self.events.extinctions.push(s.id);
+            }
+        }
+
+        let mut k = 0;
+        while k < self.species.len() {
+            if self.species[k].members == 0 {
+                // Keep order: earlier species get first pick of new members
+                self.species.remove(k);
+                self.sums.remove(k);
+            } else {
+                k += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,374 @@
+// Population statistics (TribeStats / SimStats) computed in one pass over the SoA arrays
+// Shapes match SimulationCore.getStats() in src/sim/core/simulationCore.ts: byTribe is keyed
+// by tribe name ("Tribe <id>" until a name is set with SimCore::set_tribe_info) and carries
+// the tribe colour. Genes are keyed by registry name, so extra registry genes appear as
+// extra keys in mean/distribution.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::genes::{GeneRegistry, Genes};
+use crate::types::DeathCause;
+
+// Cumulative event counts for one tribe
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct TribeCounters {
+    pub births: u32,
+    pub deaths: u32,
+    pub kills: u32,
+    pub starved: u32,
+}
+
+// Display name and hue (degrees) of a tribe, as in the JS tribeNames/tribeColors tables
+#[derive(Debug, Clone, Default)]
+pub struct TribeInfo {
+    pub name: Option<String>,
+    pub color_hue: f32,
+}
+
+// Event counters per tribe id, grown on demand
+#[derive(Default)]
+pub struct Counters {
+    tribes: Vec<TribeCounters>,
+}
+
+impl Counters {
+    fn tribe_mut(&mut self, tribe: u16) -> &mut TribeCounters {
+        let idx = tribe as usize;
+        if idx >= self.tribes.len() {
+            self.tribes.resize(idx + 1, TribeCounters::default());
+        }
+        &mut self.tribes[idx]
+    }
+
+    pub fn get(&self, tribe: u16) -> TribeCounters {
+        self.tribes.get(tribe as usize).copied().unwrap_or_default()
+    }
+
+    pub fn birth(&mut self, tribe: u16) {
+        self.tribe_mut(tribe).births += 1;
+    }
+
+    pub fn death(&mut self, tribe: u16, cause: DeathCause) {
+        let counters = self.tribe_mut(tribe);
+        counters.deaths += 1;
+        if cause == DeathCause::Starved {
+            counters.starved += 1;
+        }
+    }
+
+    // Credited to the killer's tribe
+    pub fn kill(&mut self, tribe: u16) {
+        self.tribe_mut(tribe).kills += 1;
+    }
+
+    pub fn reset(&mut self) {
+        self.tribes.clear();
+    }
+
+    // Sum over all tribes
+    pub fn totals(&self) -> TribeCounters {
+        self.tribes.iter().fold(TribeCounters::default(), |acc, c| TribeCounters {
+            births: acc.births + c.births,
+            deaths: acc.deaths + c.deaths,
+            kills: acc.kills + c.kills,
+            starved: acc.starved + c.starved,
+        })
+    }
+}
+
+// Running mean/variance (Welford) with min and max
+#[derive(Debug, Clone, Copy)]
+pub struct Welford {
+    pub n: u32,
+    pub mean: f64,
+    m2: f64,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for Welford {
+    fn default() -> Self {
+        Welford {
+            n: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+        }
+    }
+}
+
+impl Welford {
+    #[inline]
+    pub fn push(&mut self, value: f32) {
+        self.n += 1;
+        let x = value as f64;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    // Population variance (0 for fewer than two samples)
+    #[inline]
+    pub fn variance(&self) -> f64 {
+        if self.n > 1 {
+            self.m2 / self.n as f64
+        } else {
+            0.0
+        }
+    }
+
+    #[inline]
+    pub fn std(&self) -> f32 {
+        self.variance().sqrt() as f32
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneDistribution {
+    pub min: f32,
+    pub max: f32,
+    pub std: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GeneSummary {
+    pub mean: BTreeMap<String, f32>,
+    pub distribution: BTreeMap<String, GeneDistribution>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TribeStats {
+    // population and count are the same number; the UI reads both
+    pub population: u32,
+    pub count: u32,
+    pub births: u32,
+    pub deaths: u32,
+    pub kills: u32,
+    pub starved: u32,
+    pub average_age: f32,
+    pub average_energy: f32,
+    // CSS colour string and the hue it is built from
+    pub color: String,
+    pub color_hue: f32,
+    #[serde(flatten)]
+    pub genes: GeneSummary,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FoodStats {
+    pub current: f32,
+    pub capacity: f32,
+    pub percentage: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimStats {
+    pub time: f64,
+    pub population: u32,
+    pub by_tribe: BTreeMap<String, TribeStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub food: Option<FoodStats>,
+    pub global: GeneSummary,
+}
+
+fn summarize(registry: &GeneRegistry, acc: &[Welford]) -> GeneSummary {
+    let mut summary = GeneSummary::default();
+    for (spec, w) in registry.specs().iter().zip(acc) {
+        let (min, max) = if w.n > 0 { (w.min, w.max) } else { (0.0, 0.0) };
+        summary.mean.insert(spec.name.clone(), w.mean as f32);
+        summary
+            .distribution
+            .insert(spec.name.clone(), GeneDistribution { min, max, std: w.std() });
+    }
+    summary
+}
+
+// Per-gene accumulators for every living entity: (per tribe, global)
+pub fn accumulate(
+    alive: &[u8],
+    tribe_id: &[u16],
+    genes: &Genes,
+    count: usize,
+) -> (BTreeMap<u16, Vec<Welford>>, Vec<Welford>) {
+    let stride = genes.stride();
+    let mut by_tribe: BTreeMap<u16, Vec<Welford>> = BTreeMap::new();
+    let mut global = vec![Welford::default(); stride];
+
+    for i in 0..count {
+        if alive[i] == 0 {
+            continue;
+        }
+        let tribe = by_tribe
+            .entry(tribe_id[i])
+            .or_insert_with(|| vec![Welford::default(); stride]);
+        for (g, &value) in genes.entity(i).iter().enumerate() {
+            tribe[g].push(value);
+            global[g].push(value);
+        }
+    }
+
+    (by_tribe, global)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn compute(
+    alive: &[u8],
+    tribe_id: &[u16],
+    age: &[f32],
+    energy: &[f32],
+    genes: &Genes,
+    registry: &GeneRegistry,
+    counters: &Counters,
+    tribes: &[TribeInfo],
+    food: Option<FoodStats>,
+    count: usize,
+    time: f64,
+) -> SimStats {
+    let (by_tribe, global) = accumulate(alive, tribe_id, genes, count);
+    let population = global.first().map_or(0, |w| w.n);
+
+    // Age and energy sums per tribe
+    let mut vitals: BTreeMap<u16, (f64, f64)> = BTreeMap::new();
+    for i in 0..count {
+        if alive[i] != 0 {
+            let entry = vitals.entry(tribe_id[i]).or_default();
+            entry.0 += age[i] as f64;
+            entry.1 += energy[i] as f64;
+        }
+    }
+
+    let by_tribe = by_tribe
+        .into_iter()
+        .map(|(tribe, acc)| {
+            let c = counters.get(tribe);
+            let n = acc.first().map_or(0, |w| w.n);
+            let (age_sum, energy_sum) = vitals.get(&tribe).copied().unwrap_or_default();
+            let info = tribes.get(tribe as usize);
+            let name = info
+                .and_then(|t| t.name.clone())
+                .unwrap_or_else(|| format!("Tribe {}", tribe));
+            let hue = info.map_or(0.0, |t| t.color_hue);
+            let stats = TribeStats {
+                population: n,
+                count: n,
+                births: c.births,
+                deaths: c.deaths,
+                kills: c.kills,
+                starved: c.starved,
+                average_age: (age_sum / n.max(1) as f64) as f32,
+                average_energy: (energy_sum / n.max(1) as f64) as f32,
+                color: format!("hsl({}, 80%, 50%)", hue),
+                color_hue: hue,
+                genes: summarize(registry, &acc),
+            };
+            (name, stats)
+        })
+        .collect();
+
+    SimStats {
+        time,
+        population,
+        by_tribe,
+        food,
+        global: summarize(registry, &global),
+    }
+}
+
+// Bin of `value` within [min, max] split into `bins` equal bins (edges clamp into the end bins)
+#[inline]
+pub fn bin_of(value: f32, min: f32, max: f32, bins: usize) -> usize {
+    let t = (value - min) / (max - min).max(f32::EPSILON);
+    ((t * bins as f32) as isize).clamp(0, bins as isize - 1) as usize
+}
+
+// Histogram of one gene over its registry range. With `tribe` set only that tribe is counted,
+// otherwise rows of `bins` counts per tribe id are written while they fit into `out`
+// (out[tribe * bins + bin]); pass `per_tribe = false` for a single all-tribe row.
+// Returns the number of entities counted.
+#[allow(clippy::too_many_arguments)]
+pub fn gene_histogram(
+    alive: &[u8],
+    tribe_id: &[u16],
+    genes: &Genes,
+    registry: &GeneRegistry,
+    count: usize,
+    gene: usize,
+    bins: usize,
+    tribe: Option<u16>,
+    per_tribe: bool,
+    out: &mut [u32],
+) -> u32 {
+    let Some(spec) = registry.specs().get(gene) else {
+        return 0;
+    };
+    if bins == 0 {
+        return 0;
+    }
+    out.fill(0);
+
+    let mut counted = 0;
+    for i in 0..count {
+        if alive[i] == 0 || tribe.is_some_and(|t| t != tribe_id[i]) {
+            continue;
+        }
+
+        let row = if per_tribe && tribe.is_none() {
+            tribe_id[i] as usize
+        } else {
+            0
+        };
+        let slot = row * bins + bin_of(genes.get(i, gene), spec.min, spec.max, bins);
+        if let Some(cell) = out.get_mut(slot) {
+            *cell += 1;
+            counted += 1;
+        }
+    }
+
+    counted
+}
+
+// Joint density of two genes, row-major: out[y_bin * bins_x + x_bin]
+#[allow(clippy::too_many_arguments)]
+pub fn joint_histogram(
+    alive: &[u8],
+    tribe_id: &[u16],
+    genes: &Genes,
+    registry: &GeneRegistry,
+    count: usize,
+    gene_x: usize,
+    gene_y: usize,
+    bins_x: usize,
+    bins_y: usize,
+    tribe: Option<u16>,
+    out: &mut [u32],
+) -> u32 {
+    let (Some(spec_x), Some(spec_y)) = (registry.specs().get(gene_x), registry.specs().get(gene_y))
+    else {
+        return 0;
+    };
+    if bins_x == 0 || bins_y == 0 || out.len() < bins_x * bins_y {
+        return 0;
+    }
+    out.fill(0);
+
+    let mut counted = 0;
+    for i in 0..count {
+        if alive[i] == 0 || tribe.is_some_and(|t| t != tribe_id[i]) {
+            continue;
+        }
+
+        let bx = bin_of(genes.get(i, gene_x), spec_x.min, spec_x.max, bins_x);
+        let by = bin_of(genes.get(i, gene_y), spec_y.min, spec_y.max, bins_y);
+        out[by * bins_x + bx] += 1;
+        counted += 1;
+    }
+
+    counted
+}
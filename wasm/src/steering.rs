@@ -0,0 +1,561 @@
+// Pluggable steering behaviors
+// Each behavior turns the perception of one entity into a steering vector; the pipeline
+// sums them with per-behavior weights that can be overridden per tribe.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::collision::BiomeCollisionMap;
+use crate::food::FoodGrid;
+use crate::movement::Perception;
+use crate::pathfinding::Pathfinder;
+use crate::types::BehaviorState;
+
+// Energy below which foraging switches to the wide, urgent search
+const DESPERATE_ENERGY: f32 = 40.0;
+const FORAGE_FORCE: f32 = 3.0;
+const FLEE_FORCE: f32 = 4.0;
+const MIGRATION_FORCE: f32 = 2.5;
+// Neighbor count above which flocking is damped
+const FLOCK_CROWD_LIMIT: f32 = 10.0;
+// Separation boost at full crowd stress (scaled by stress²)
+const CROWD_SEPARATION_GAIN: f32 = 3.0;
+// Ring radius pack hunters spread around before closing in
+const SURROUND_RADIUS: f32 = 30.0;
+// Seconds of travel the obstacle probe looks ahead
+const OBSTACLE_LOOKAHEAD: f32 = 0.75;
+// Probe rotations (radians) tried when the path ahead is blocked
+const OBSTACLE_PROBE_ANGLES: [f32; 8] = [0.5, -0.5, 1.0, -1.0, 1.6, -1.6, 2.4, -2.4];
+
+// Everything a behavior may look at for the entity being steered
+pub struct SteeringContext<'a> {
+    pub index: usize,
+    pub px: f32,
+    pub py: f32,
+    pub tribe: u16,
+    pub energy: f32,
+    pub satiation: f32,
+    pub effective_speed: f32,
+    pub vision: f32,
+    pub cohesion: f32,
+    pub food_standards: f32,
+    pub diet: f32,
+    pub view_dir: (f32, f32),
+    pub view_cos_threshold: f32,
+    pub hunting_threshold: f32,
+    // 0 when starving (hunger overrides fear), up to 1 when well fed
+    pub bravery: f32,
+    pub herd_panic: f32,
+    pub state: BehaviorState,
+    // Persistent unit heading, meaningful while migrating
+    pub migration_heading: (f32, f32),
+    pub perception: &'a Perception,
+    pub pos_x: &'a [f32],
+    pub pos_y: &'a [f32],
+    pub terrain: Option<&'a BiomeCollisionMap>,
+    pub food: Option<&'a FoodGrid>,
+    pub pathfinder: Option<&'a mut Pathfinder>,
+    // Food patch the foraging behavior steered toward, written back for the inspector
+    pub food_target: Option<(f32, f32)>,
+}
+
+pub trait SteeringBehavior {
+    // Stable identifier used by JS and scenario configs
+    fn name(&self) -> &'static str;
+
+    // Unweighted steering contribution
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32);
+}
+
+// Flocking fades once more than FLOCK_CROWD_LIMIT neighbors are in view
+#[inline]
+fn flocking_damping(p: &Perception) -> f32 {
+    let count = p.nearby_count as f32;
+    if count > FLOCK_CROWD_LIMIT {
+        FLOCK_CROWD_LIMIT / count
+    } else {
+        1.0
+    }
+}
+
+#[inline]
+fn normalized(x: f32, y: f32) -> Option<(f32, f32)> {
+    let mag = (x * x + y * y).sqrt();
+    if mag > 0.001 {
+        Some((x / mag, y / mag))
+    } else {
+        None
+    }
+}
+
+// Match the average heading of visible allies
+pub struct Alignment;
+
+impl SteeringBehavior for Alignment {
+    fn name(&self) -> &'static str {
+        "alignment"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let p = ctx.perception;
+        if p.nearby_allies < 2 {
+            return (0.0, 0.0);
+        }
+
+        let strength = ctx.cohesion * flocking_damping(p);
+        normalized(p.align_x, p.align_y)
+            .map(|(x, y)| (x * strength, y * strength))
+            .unwrap_or((0.0, 0.0))
+    }
+}
+
+// Move toward the center of visible allies
+pub struct Cohesion;
+
+impl SteeringBehavior for Cohesion {
+    fn name(&self) -> &'static str {
+        "cohesion"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let p = ctx.perception;
+        if p.nearby_allies == 0 {
+            return (0.0, 0.0);
+        }
+
+        let n = p.nearby_allies as f32;
+        let strength = ctx.cohesion * flocking_damping(p);
+        normalized(p.cohesion_x / n - ctx.px, p.cohesion_y / n - ctx.py)
+            .map(|(x, y)| (x * strength, y * strength))
+            .unwrap_or((0.0, 0.0))
+    }
+}
+
+// Keep personal space from close allies, pushing harder in crowds
+pub struct Separation;
+
+impl SteeringBehavior for Separation {
+    fn name(&self) -> &'static str {
+        "separation"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let p = ctx.perception;
+        let strength = 1.0 + CROWD_SEPARATION_GAIN * p.crowd_stress * p.crowd_stress;
+        normalized(p.separate_x, p.separate_y)
+            .map(|(x, y)| (x * strength, y * strength))
+            .unwrap_or((0.0, 0.0))
+    }
+}
+
+// Pursue the selected prey, routing around terrain when needed
+pub struct Hunting;
+
+impl SteeringBehavior for Hunting {
+    fn name(&self) -> &'static str {
+        "hunting"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let Some(prey_idx) = ctx.perception.best_prey else {
+            return (0.0, 0.0);
+        };
+
+        let prey_x = ctx.pos_x[prey_idx];
+        let prey_y = ctx.pos_y[prey_idx];
+        let (mut goal_x, mut goal_y) = (prey_x, prey_y);
+
+        // Pack members fan out to different points on a ring around the prey, then close in
+        let p = ctx.perception;
+        if p.pack_size > 0 {
+            let away_x = ctx.px - prey_x;
+            let away_y = ctx.py - prey_y;
+            let dist_sq = away_x * away_x + away_y * away_y;
+
+            if dist_sq > SURROUND_RADIUS * SURROUND_RADIUS * 2.25 {
+                let members = (p.pack_size + 1) as f32;
+                let spread = std::f32::consts::TAU / members;
+                let offset = (p.pack_slot as f32 - p.pack_size as f32 / 2.0) * spread;
+                let angle = away_y.atan2(away_x) + offset;
+                goal_x = prey_x + angle.cos() * SURROUND_RADIUS;
+                goal_y = prey_y + angle.sin() * SURROUND_RADIUS;
+            }
+        }
+
+        let Some(straight) = normalized(goal_x - ctx.px, goal_y - ctx.py) else {
+            return (0.0, 0.0);
+        };
+
+        // Route around terrain when the straight line to the goal is blocked
+        let (dir_x, dir_y) = match (ctx.terrain, ctx.pathfinder.as_deref_mut()) {
+            (Some(map), Some(pf)) => pf
+                .steer_toward(map, ctx.index, ctx.px, ctx.py, goal_x, goal_y)
+                .unwrap_or(straight),
+            _ => straight,
+        };
+
+        // Strong pursuit force
+        let hunger_desperation =
+            ((ctx.hunting_threshold - ctx.energy) / ctx.hunting_threshold).max(0.0);
+        let hunt_force = 3.0 + hunger_desperation * 2.0;
+        (dir_x * hunt_force, dir_y * hunt_force)
+    }
+}
+
+// Run from predators, or with panicking herd mates
+pub struct Fleeing;
+
+impl SteeringBehavior for Fleeing {
+    fn name(&self) -> &'static str {
+        "fleeing"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let p = ctx.perception;
+        if ctx.bravery <= 0.0 {
+            return (0.0, 0.0);
+        }
+
+        if p.threat_level > 0.0 {
+            if let Some((x, y)) = normalized(p.flee_x, p.flee_y) {
+                let flee_force = FLEE_FORCE * p.threat_level * ctx.bravery;
+                return (x * flee_force, y * flee_force);
+            }
+        } else if ctx.herd_panic > 0.0 && p.ally_panic > 0.0 {
+            // No threat of our own in sight - run with the most alarmed neighbor
+            if let Some((x, y)) = normalized(p.ally_panic_vx, p.ally_panic_vy) {
+                let panic_force = FLEE_FORCE * p.ally_panic * ctx.herd_panic * ctx.bravery;
+                return (x * panic_force, y * panic_force);
+            }
+        }
+
+        (0.0, 0.0)
+    }
+}
+
+// Seek plant food within vision, weighted by pickiness, diet and hunger
+pub struct Foraging;
+
+impl SteeringBehavior for Foraging {
+    fn name(&self) -> &'static str {
+        "foraging"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let Some(grid) = ctx.food else {
+            return (0.0, 0.0);
+        };
+
+        // Skipped while chasing prey or when nearly full
+        if ctx.perception.best_prey.is_some() || ctx.satiation >= 0.95 {
+            return (0.0, 0.0);
+        }
+
+        // Plants are worth less to carnivores
+        let plant_efficiency = if ctx.diet < 0.0 {
+            1.0
+        } else {
+            (1.0 - ctx.diet.abs()).max(0.3)
+        };
+        let hunger = 1.0 - ctx.satiation;
+        let desperate = ctx.energy < DESPERATE_ENERGY;
+        let desperation = (DESPERATE_ENERGY - ctx.energy) / DESPERATE_ENERGY;
+
+        // Hungrier entities scan wider, limited by vision
+        let vision_cells = (ctx.vision / grid.min_cell_extent()).ceil() as i32;
+        let mut scan_radius = vision_cells.min((hunger * 5.0 + 1.0).ceil() as i32);
+        if !desperate {
+            scan_radius = ((scan_radius as f32 * 0.6) as i32).max(1);
+        }
+
+        // Pickiness relaxes with hunger
+        let min_food = ctx.food_standards * (0.3 + ctx.satiation * 0.7);
+
+        let patch = grid.best_patch(
+            ctx.px,
+            ctx.py,
+            scan_radius,
+            ctx.vision,
+            ctx.view_dir,
+            ctx.view_cos_threshold,
+            min_food,
+        );
+
+        if let Some((food_x, food_y, _)) = patch {
+            ctx.food_target = Some((food_x, food_y));
+            let Some((dir_x, dir_y)) = normalized(food_x - ctx.px, food_y - ctx.py) else {
+                return (0.0, 0.0);
+            };
+
+            let urgency = if desperate {
+                0.3 + desperation * 0.4
+            } else if grid.at_world(ctx.px, ctx.py) > min_food && ctx.satiation > 0.6 {
+                // Already on a good patch - just drift
+                0.05
+            } else {
+                0.1 + hunger * 0.5
+            };
+            let forage_force = urgency * plant_efficiency * FORAGE_FORCE;
+            (dir_x * forage_force, dir_y * forage_force)
+        } else if desperate || hunger > 0.2 {
+            // Nothing acceptable in sight - push on along the current heading to leave the area
+            let urgency = if desperate {
+                0.4 + desperation * 0.5
+            } else {
+                0.2 + hunger * 0.3
+            };
+            let explore_force = urgency * plant_efficiency * FORAGE_FORCE;
+            (ctx.view_dir.0 * explore_force, ctx.view_dir.1 * explore_force)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+}
+
+// Hold the remembered heading while migrating away from a depleted or crowded area
+pub struct Migration;
+
+impl SteeringBehavior for Migration {
+    fn name(&self) -> &'static str {
+        "migration"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        if ctx.state != BehaviorState::Migrating {
+            return (0.0, 0.0);
+        }
+
+        // Hungrier migrants travel with more purpose
+        let force = MIGRATION_FORCE * (0.5 + (1.0 - ctx.satiation) * 0.5);
+        let (hx, hy) = ctx.migration_heading;
+        (hx * force, hy * force)
+    }
+}
+
+// Follow the tribe's flow field (food areas, tribe home) when not chasing prey
+pub struct FlowFollowing;
+
+impl SteeringBehavior for FlowFollowing {
+    fn name(&self) -> &'static str {
+        "flow"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        if ctx.perception.best_prey.is_some() {
+            return (0.0, 0.0);
+        }
+
+        match (ctx.terrain, ctx.pathfinder.as_deref()) {
+            (Some(map), Some(pf)) => pf
+                .tribe_flow_direction(map, ctx.tribe, ctx.px, ctx.py)
+                .map(|(x, y, weight)| (x * weight, y * weight))
+                .unwrap_or((0.0, 0.0)),
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+// Small pseudo-random drift
+pub struct Wander;
+
+impl SteeringBehavior for Wander {
+    fn name(&self) -> &'static str {
+        "wander"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let wander_angle =
+            ((ctx.index as f32 * 12.34 + ctx.px * 56.78) % 1.0) * std::f32::consts::TAU;
+        (wander_angle.cos(), wander_angle.sin())
+    }
+}
+
+// Turn away from impassable terrain ahead
+pub struct ObstacleAvoidance;
+
+impl SteeringBehavior for ObstacleAvoidance {
+    fn name(&self) -> &'static str {
+        "obstacle_avoidance"
+    }
+
+    fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let Some(map) = ctx.terrain else {
+            return (0.0, 0.0);
+        };
+
+        let (hx, hy) = ctx.view_dir;
+        let lookahead = (ctx.effective_speed * OBSTACLE_LOOKAHEAD).max(map.cell_size() * 1.5);
+
+        if map.is_world_traversable(ctx.px + hx * lookahead, ctx.py + hy * lookahead) {
+            return (0.0, 0.0);
+        }
+
+        // Rotate the probe until it finds open ground
+        for angle in OBSTACLE_PROBE_ANGLES {
+            let (sin, cos) = angle.sin_cos();
+            let dir_x = hx * cos - hy * sin;
+            let dir_y = hx * sin + hy * cos;
+            if map.is_world_traversable(ctx.px + dir_x * lookahead, ctx.py + dir_y * lookahead) {
+                return (dir_x, dir_y);
+            }
+        }
+
+        (-hx, -hy)
+    }
+}
+
+// Weights keyed by behavior name, as exchanged with JS / scenario files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SteeringConfig {
+    #[serde(default)]
+    pub weights: HashMap<String, f32>,
+    #[serde(default)]
+    pub tribes: Vec<TribeSteeringConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TribeSteeringConfig {
+    pub tribe: u16,
+    pub weights: HashMap<String, f32>,
+}
+
+pub struct SteeringPipeline {
+    behaviors: Vec<Box<dyn SteeringBehavior>>,
+    weights: Vec<f32>,
+    tribe_weights: HashMap<u16, Vec<f32>>,
+}
+
+impl Default for SteeringPipeline {
+    // Built-in behaviors with the weights the hand-tuned model shipped with
+    fn default() -> Self {
+        let mut pipeline = SteeringPipeline::new();
+        pipeline.register(Box::new(Alignment), 0.5);
+        pipeline.register(Box::new(Cohesion), 0.3);
+        pipeline.register(Box::new(Separation), 2.0);
+        pipeline.register(Box::new(Hunting), 1.0);
+        pipeline.register(Box::new(FlowFollowing), 1.0);
+        pipeline.register(Box::new(Fleeing), 1.0);
+        pipeline.register(Box::new(Foraging), 1.0);
+        pipeline.register(Box::new(Migration), 1.0);
+        pipeline.register(Box::new(Wander), 0.1);
+        // Off by default so existing tuning is unchanged; enable via configure_steering
+        pipeline.register(Box::new(ObstacleAvoidance), 0.0);
+        pipeline
+    }
+}
+
+impl SteeringPipeline {
+    pub fn new() -> Self {
+        SteeringPipeline {
+            behaviors: Vec::new(),
+            weights: Vec::new(),
+            tribe_weights: HashMap::new(),
+        }
+    }
+
+    // Add a behavior; tribes with overrides get it at the default weight
+    pub fn register(&mut self, behavior: Box<dyn SteeringBehavior>, weight: f32) {
+        self.behaviors.push(behavior);
+        self.weights.push(weight);
+        for weights in self.tribe_weights.values_mut() {
+            weights.push(weight);
+        }
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.behaviors.iter().position(|b| b.name() == name)
+    }
+
+    // Default weight for every tribe without an override (0 disables)
+    pub fn set_weight(&mut self, name: &str, weight: f32) -> bool {
+        let Some(idx) = self.index_of(name) else {
+            return false;
+        };
+        self.weights[idx] = weight;
+        true
+    }
+
+    pub fn set_tribe_weight(&mut self, tribe: u16, name: &str, weight: f32) -> bool {
+        let Some(idx) = self.index_of(name) else {
+            return false;
+        };
+        let defaults = &self.weights;
+        self.tribe_weights
+            .entry(tribe)
+            .or_insert_with(|| defaults.clone())[idx] = weight;
+        true
+    }
+
+    pub fn clear_tribe_weights(&mut self, tribe: u16) {
+        self.tribe_weights.remove(&tribe);
+    }
+
+    // All names are checked first, so a bad config leaves the pipeline untouched
+    pub fn apply_config(&mut self, config: &SteeringConfig) -> Result<(), String> {
+        let names = config
+            .weights
+            .keys()
+            .chain(config.tribes.iter().flat_map(|tribe| tribe.weights.keys()));
+        for name in names {
+            if self.index_of(name).is_none() {
+                return Err(format!("Unknown steering behavior: {}", name));
+            }
+        }
+
+        for (name, &weight) in &config.weights {
+            self.set_weight(name, weight);
+        }
+        for tribe in &config.tribes {
+            for (name, &weight) in &tribe.weights {
+                self.set_tribe_weight(tribe.tribe, name, weight);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn config(&self) -> SteeringConfig {
+        let named = |weights: &[f32]| {
+            self.behaviors
+                .iter()
+                .zip(weights)
+                .map(|(b, &w)| (b.name().to_string(), w))
+                .collect()
+        };
+
+        let mut tribes: Vec<TribeSteeringConfig> = self
+            .tribe_weights
+            .iter()
+            .map(|(&tribe, weights)| TribeSteeringConfig {
+                tribe,
+                weights: named(weights),
+            })
+            .collect();
+        tribes.sort_by_key(|t| t.tribe);
+
+        SteeringConfig {
+            weights: named(&self.weights),
+            tribes,
+        }
+    }
+
+    // Weighted sum of all enabled behaviors for this entity
+    pub fn steer(&self, ctx: &mut SteeringContext) -> (f32, f32) {
+        let weights = self.tribe_weights.get(&ctx.tribe).unwrap_or(&self.weights);
+
+        let mut steer_x = 0.0;
+        let mut steer_y = 0.0;
+
+        for (behavior, &weight) in self.behaviors.iter().zip(weights) {
+            if weight == 0.0 {
+                continue;
+            }
+            let (x, y) = behavior.steer(ctx);
+            steer_x += x * weight;
+            steer_y += y * weight;
+        }
+
+        (steer_x, steer_y)
+    }
+}
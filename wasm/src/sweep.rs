@@ -0,0 +1,493 @@
+// Parameter sweeps: a scenario, a grid of parameter values and a set of seeds
+// Every combination of parameter values is one configuration; each configuration runs once
+// per seed (the same seeds for every configuration), all runs in parallel. Parameters are
+// dotted paths into the scenario JSON, array elements by index:
+//
+//   {
+//     "scenario": { ... } or "baseline.json",
+//     "seeds": 8,                                   // or [11, 12, 13]
+//     "ticks": 36000,
+//     "parameters": {
+//       "food.regen": [0.02, 0.05, 0.1],
+//       "energy.base_cost": { "from": 1.0, "to": 2.0, "steps": 5 },
+//       "tribes.1.genes.aggression": [0.5, 0.9]
+//     },
+//     "prey_tribes": [0], "predator_tribes": [1]    // default: classified by founder diet
+//   }
+
+use std::collections::BTreeMap;
+
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::analysis::{self, AnalysisConfig, PopulationEvent, PopulationReport};
+use crate::scenario::Scenario;
+use crate::evolution;
+use crate::export::csv_field;
+use crate::stats;
+use crate::SimCore;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Seeds {
+    // Seeds 1..=n
+    Count(u32),
+    List(Vec<u32>),
+}
+
+impl Seeds {
+    fn values(&self) -> Vec<u32> {
+        match self {
+            Seeds::Count(n) => (1..=*n).collect(),
+            Seeds::List(seeds) => seeds.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterRange {
+    Values(Vec<Value>),
+    // `steps` evenly spaced values from `from` to `to` inclusive
+    Linear { from: f64, to: f64, steps: usize },
+}
+
+impl ParameterRange {
+    fn values(&self) -> Vec<Value> {
+        match self {
+            ParameterRange::Values(values) => values.clone(),
+            ParameterRange::Linear { from, to, steps } => {
+                let steps = *steps;
+                (0..steps)
+                    .map(|k| {
+                        let t = if steps > 1 { k as f64 / (steps - 1) as f64 } else { 0.0 };
+                        Value::from(from + (to - from) * t)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawSweepSpec")]
+pub struct SweepSpec {
+    // Scenario object (a file name is resolved by the caller)
+    pub scenario: Value,
+    pub seeds: Seeds,
+    pub ticks: Option<u64>,
+    pub parameters: BTreeMap<String, ParameterRange>,
+    pub prey_tribes: Option<Vec<u16>>,
+    pub predator_tribes: Option<Vec<u16>>,
+    pub analysis: AnalysisConfig,
+}
+
+// SweepSpec as written, checked so the grid always has at least one run
+#[derive(Deserialize)]
+struct RawSweepSpec {
+    scenario: Value,
+    #[serde(default = "default_seeds")]
+    seeds: Seeds,
+    #[serde(default)]
+    ticks: Option<u64>,
+    #[serde(default)]
+    parameters: BTreeMap<String, ParameterRange>,
+    #[serde(default)]
+    prey_tribes: Option<Vec<u16>>,
+    #[serde(default)]
+    predator_tribes: Option<Vec<u16>>,
+    #[serde(default)]
+    analysis: AnalysisConfig,
+}
+
+fn default_seeds() -> Seeds {
+    Seeds::Count(4)
+}
+
+impl TryFrom<RawSweepSpec> for SweepSpec {
+    type Error = String;
+
+    fn try_from(raw: RawSweepSpec) -> Result<Self, String> {
+        match &raw.seeds {
+            Seeds::Count(0) => return Err("seeds: need at least one seed".into()),
+            Seeds::List(seeds) if seeds.is_empty() => return Err("seeds: empty seed list".into()),
+            _ => {}
+        }
+        for (name, range) in &raw.parameters {
+            match range {
+                ParameterRange::Values(values) if values.is_empty() => {
+                    return Err(format!("parameters.{}: empty value list", name));
+                }
+                ParameterRange::Linear { steps: 0, .. } => {
+                    return Err(format!("parameters.{}: steps must be at least 1", name));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(SweepSpec {
+            scenario: raw.scenario,
+            seeds: raw.seeds,
+            ticks: raw.ticks,
+            parameters: raw.parameters,
+            prey_tribes: raw.prey_tribes,
+            predator_tribes: raw.predator_tribes,
+            analysis: raw.analysis,
+        })
+    }
+}
+
+// Outcome of one seed of one configuration
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub config: usize,
+    pub seed: u32,
+    pub ticks: u64,
+    pub population: u32,
+    pub prey: u32,
+    pub predators: u32,
+    pub species: usize,
+    // Mean pairwise genetic distance of the final population
+    pub diversity: f64,
+    pub gene_means: Vec<f64>,
+    pub report: PopulationReport,
+}
+
+impl RunResult {
+    fn collapses(&self) -> usize {
+        self.report
+            .events
+            .iter()
+            .filter(|e| matches!(e, PopulationEvent::Collapse { .. }))
+            .count()
+    }
+}
+
+// Seed-averaged outcome of one configuration
+#[derive(Debug, Clone)]
+pub struct ConfigSummary {
+    pub parameters: Vec<(String, Value)>,
+    pub runs: usize,
+    // Fraction of seeds with any / prey / predator survivors at the end
+    pub survival: f64,
+    pub prey_survival: f64,
+    pub predator_survival: f64,
+    pub population: f64,
+    pub species: f64,
+    pub diversity: f64,
+    // Means over the runs that oscillated, and the fraction that did
+    pub oscillating: f64,
+    pub prey_period: Option<f64>,
+    pub prey_amplitude: Option<f64>,
+    pub predator_period: Option<f64>,
+    pub predator_amplitude: Option<f64>,
+    pub collapses: f64,
+    pub gene_means: Vec<f64>,
+}
+
+pub struct SweepResult {
+    pub gene_names: Vec<String>,
+    pub parameter_names: Vec<String>,
+    pub configs: Vec<ConfigSummary>,
+    pub runs: Vec<RunResult>,
+}
+
+// Set a dotted path (objects by key, arrays by index) inside a JSON value
+fn set_path(root: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let mut node = root;
+    for key in path.split('.') {
+        node = match node {
+            Value::Array(items) => key
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .ok_or_else(|| format!("{}: no element '{}'", path, key))?,
+            Value::Object(map) => map.entry(key).or_insert(Value::Object(Default::default())),
+            _ => return Err(format!("{}: '{}' is not inside an object or array", path, key)),
+        };
+    }
+    *node = value;
+    Ok(())
+}
+
+// Cartesian product of all parameter values, first parameter varying slowest
+fn grid(parameters: &BTreeMap<String, ParameterRange>) -> Vec<Vec<(String, Value)>> {
+    let mut configs = vec![Vec::new()];
+    for (name, range) in parameters {
+        let values = range.values();
+        configs = configs
+            .into_iter()
+            .flat_map(|config| {
+                values.iter().map(move |value| {
+                    let mut next = config.clone();
+                    next.push((name.clone(), value.clone()));
+                    next
+                })
+            })
+            .collect();
+    }
+    configs
+}
+
+// Tribes whose founders eat mostly meat are predators, the rest prey
+fn classify_tribes(sim: &SimCore) -> (Vec<u16>, Vec<u16>) {
+    let mut diet: BTreeMap<u16, (f32, u32)> = BTreeMap::new();
+    for i in 0..sim.count {
+        if sim.alive[i] != 0 {
+            let entry = diet.entry(sim.tribe_id[i]).or_default();
+            entry.0 += sim.genes.diet(i);
+            entry.1 += 1;
+        }
+    }
+    let mut prey = Vec::new();
+    let mut predators = Vec::new();
+    for (tribe, (sum, n)) in diet {
+        if sum / n as f32 > 0.0 {
+            predators.push(tribe);
+        } else {
+            prey.push(tribe);
+        }
+    }
+    (prey, predators)
+}
+
+fn run_one(spec: &SweepSpec, config: usize, scenario: &Value, seed: u32) -> Result<RunResult, String> {
+    let mut scenario: Scenario = serde_json::from_value(scenario.clone()).map_err(|e| e.to_string())?;
+    scenario.set_seed(seed);
+    if let Some(ticks) = spec.ticks {
+        scenario.set_ticks(ticks);
+    }
+
+    let mut sim = scenario.build()?;
+    let (default_prey, default_predators) = classify_tribes(&sim);
+    let prey_tribes = spec.prey_tribes.clone().unwrap_or(default_prey);
+    let predator_tribes = spec.predator_tribes.clone().unwrap_or(default_predators);
+
+    let dt = scenario.dt();
+    for _ in 0..scenario.ticks() {
+        sim.step(dt);
+        if sim.living_count() == 0 {
+            break;
+        }
+    }
+    sim.update_species();
+
+    let (by_tribe, global) = stats::accumulate(&sim.alive, &sim.tribe_id, &sim.genes, sim.count);
+    let tribe_total = |tribes: &[u16]| -> u32 {
+        tribes
+            .iter()
+            .filter_map(|t| by_tribe.get(t))
+            .map(|acc| acc.first().map_or(0, |w| w.n))
+            .sum()
+    };
+    let living: Vec<usize> = (0..sim.count).filter(|&i| sim.alive[i] != 0).collect();
+    let diversity = evolution::mean_pairwise_distance(&living, &sim.genes, &sim.gene_registry) as f64;
+
+    Ok(RunResult {
+        config,
+        seed,
+        ticks: sim.tick,
+        population: sim.living_count(),
+        prey: tribe_total(&prey_tribes),
+        predators: tribe_total(&predator_tribes),
+        species: sim.species.species().len(),
+        diversity,
+        gene_means: global.iter().map(|w| w.mean).collect(),
+        report: sim.population_report(&prey_tribes, &predator_tribes, 0.0, f64::MAX, &spec.analysis),
+    })
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+fn summarize(parameters: Vec<(String, Value)>, runs: &[&RunResult]) -> ConfigSummary {
+    let n = runs.len().max(1) as f64;
+    let fraction = |f: &dyn Fn(&RunResult) -> bool| runs.iter().filter(|r| f(r)).count() as f64 / n;
+    let average = |f: &dyn Fn(&RunResult) -> f64| mean(runs.iter().map(|r| f(r))).unwrap_or(0.0);
+    let oscillation_mean = |f: &dyn Fn(&analysis::Oscillation) -> f64, prey: bool| {
+        mean(runs.iter().filter_map(|r| {
+            let summary = if prey { &r.report.prey } else { &r.report.predator };
+            summary.oscillation.as_ref().map(f)
+        }))
+    };
+
+    let genes = runs.first().map_or(0, |r| r.gene_means.len());
+    // Gene means over the runs that still had a population
+    let survivors: Vec<&&RunResult> = runs.iter().filter(|r| r.population > 0).collect();
+    let gene_means = (0..genes)
+        .map(|g| mean(survivors.iter().map(|r| r.gene_means[g])).unwrap_or(0.0))
+        .collect();
+
+    ConfigSummary {
+        parameters,
+        runs: runs.len(),
+        survival: fraction(&|r| r.population > 0),
+        prey_survival: fraction(&|r| r.prey > 0),
+        predator_survival: fraction(&|r| r.predators > 0),
+        population: average(&|r| r.population as f64),
+        species: average(&|r| r.species as f64),
+        diversity: average(&|r| r.diversity),
+        oscillating: fraction(&|r| r.report.prey.oscillation.is_some()),
+        prey_period: oscillation_mean(&|o| o.period, true),
+        prey_amplitude: oscillation_mean(&|o| o.amplitude, true),
+        predator_period: oscillation_mean(&|o| o.period, false),
+        predator_amplitude: oscillation_mean(&|o| o.amplitude, false),
+        collapses: average(&|r| r.collapses() as f64),
+        gene_means,
+    }
+}
+
+// Run every configuration × seed in parallel on the current rayon pool
+pub fn run(spec: &SweepSpec) -> Result<SweepResult, String> {
+    let configs = grid(&spec.parameters);
+    let mut scenarios = Vec::with_capacity(configs.len());
+    for config in &configs {
+        let mut scenario = spec.scenario.clone();
+        for (path, value) in config {
+            set_path(&mut scenario, path, value.clone())?;
+        }
+        scenarios.push(scenario);
+    }
+
+    // Fail early on a bad scenario instead of once per run
+    let probe: Scenario = serde_json::from_value(scenarios[0].clone()).map_err(|e| e.to_string())?;
+    let gene_names = probe.build()?.gene_registry.specs().iter().map(|g| g.name.clone()).collect();
+
+    let seeds = spec.seeds.values();
+    let jobs: Vec<(usize, u32)> = (0..configs.len())
+        .flat_map(|c| seeds.iter().map(move |&seed| (c, seed)))
+        .collect();
+    let runs = jobs
+        .par_iter()
+        .map(|&(config, seed)| run_one(spec, config, &scenarios[config], seed))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let summaries = configs
+        .into_iter()
+        .enumerate()
+        .map(|(c, parameters)| {
+            let config_runs: Vec<&RunResult> = runs.iter().filter(|r| r.config == c).collect();
+            summarize(parameters, &config_runs)
+        })
+        .collect();
+
+    Ok(SweepResult {
+        gene_names,
+        parameter_names: spec.parameters.keys().cloned().collect(),
+        configs: summaries,
+        runs,
+    })
+}
+
+// Strings unquoted, everything else (arrays, objects) as JSON, quoted if it would break the row
+fn value_field(value: &Value) -> String {
+    match value {
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map_or_else(String::new, |v| v.to_string())
+}
+
+// One row per configuration: parameters, survival, diversity, oscillation metrics, gene means
+pub fn summary_csv(result: &SweepResult) -> String {
+    let mut header = vec!["config".to_string()];
+    header.extend(result.parameter_names.iter().map(|name| csv_field(name)));
+    header.extend(
+        [
+            "runs",
+            "survival",
+            "prey_survival",
+            "predator_survival",
+            "population",
+            "species",
+            "diversity",
+            "oscillating",
+            "prey_period",
+            "prey_amplitude",
+            "predator_period",
+            "predator_amplitude",
+            "collapses",
+        ]
+        .map(String::from),
+    );
+    header.extend(result.gene_names.iter().map(|name| csv_field(&format!("mean_{}", name))));
+
+    let mut out = header.join(",");
+    out.push('\n');
+    for (c, config) in result.configs.iter().enumerate() {
+        let mut row = vec![c.to_string()];
+        row.extend(config.parameters.iter().map(|(_, value)| value_field(value)));
+        row.extend([
+            config.runs.to_string(),
+            config.survival.to_string(),
+            config.prey_survival.to_string(),
+            config.predator_survival.to_string(),
+            config.population.to_string(),
+            config.species.to_string(),
+            config.diversity.to_string(),
+            config.oscillating.to_string(),
+            optional(config.prey_period),
+            optional(config.prey_amplitude),
+            optional(config.predator_period),
+            optional(config.predator_amplitude),
+            config.collapses.to_string(),
+        ]);
+        row.extend(config.gene_means.iter().map(|m| m.to_string()));
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// One row per run, for looking at the spread behind each summary row
+pub fn runs_csv(result: &SweepResult) -> String {
+    let mut header = vec!["config", "seed", "ticks", "population", "prey", "predators", "species", "diversity"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    header.extend(
+        ["prey_period", "prey_amplitude", "predator_period", "predator_amplitude", "predator_lag"]
+            .map(String::from),
+    );
+    header.extend(
+        ["alpha", "beta", "gamma", "delta", "prey_extinction", "predator_extinction", "collapses"].map(String::from),
+    );
+
+    let mut out = header.join(",");
+    out.push('\n');
+    for run in &result.runs {
+        let report = &run.report;
+        let lv = report.lotka_volterra.as_ref();
+        let row = [
+            run.config.to_string(),
+            run.seed.to_string(),
+            run.ticks.to_string(),
+            run.population.to_string(),
+            run.prey.to_string(),
+            run.predators.to_string(),
+            run.species.to_string(),
+            run.diversity.to_string(),
+            optional(report.prey.oscillation.map(|o| o.period)),
+            optional(report.prey.oscillation.map(|o| o.amplitude)),
+            optional(report.predator.oscillation.map(|o| o.period)),
+            optional(report.predator.oscillation.map(|o| o.amplitude)),
+            optional(report.predator_lag),
+            optional(lv.map(|lv| lv.alpha)),
+            optional(lv.map(|lv| lv.beta)),
+            optional(lv.map(|lv| lv.gamma)),
+            optional(lv.map(|lv| lv.delta)),
+            optional(report.prey.extinction_time),
+            optional(report.predator.extinction_time),
+            run.collapses().to_string(),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
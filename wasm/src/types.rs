@@ -86,4 +86,41 @@ impl PerfMetrics {
             entities_processed: 0,
         }
     }
-}
\ No newline at end of file
+}
+
+// Why an entity died (shared by energy, combat and reporting)
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum DeathCause {
+    Starved = 0,
+    OldAge = 1,
+    Killed = 2,
+    // Removed outside the core, e.g. a slot JS reused without telling the core first
+    Unknown = 3,
+}
+
+// High-level intent of an entity (see behavior.rs for the transitions)
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum BehaviorState {
+    #[default]
+    Foraging = 0,
+    Migrating = 1,
+    Hunting = 2,
+    Fleeing = 3,
+    Resting = 4,
+}
+
+impl BehaviorState {
+    pub fn from_u8(value: u8) -> BehaviorState {
+        match value {
+            1 => BehaviorState::Migrating,
+            2 => BehaviorState::Hunting,
+            3 => BehaviorState::Fleeing,
+            4 => BehaviorState::Resting,
+            _ => BehaviorState::Foraging,
+        }
+    }
+}